@@ -0,0 +1,95 @@
+//! # Ferrisyum Map Convert
+//!
+//! Converts maps between the ASCII format and structured RON
+//! [MapDocument](ferrisyum::world::MapDocument)s. The direction is picked from the input's
+//! extension: a `.ron` document is rendered as an ASCII map, a Tiled map (`.tmj` / `.tmx`) is
+//! imported as a document, and anything else is converted to a document. Only maps which load
+//! without errors are converted.
+//!
+//! ```text
+//! ferrisyum-mapconvert <INPUT> [OUTPUT]
+//! ```
+//!
+//! The result is written to `OUTPUT`, or printed if no output is given. Exits with `1` if the map
+//! cannot be converted, or `2` if the arguments or registries are invalid.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::path::Path;
+use std::process::ExitCode;
+
+// > USE CRATE
+use ferrisyum::registry::{Registries, ITEMS_PATH, TILES_PATH};
+use ferrisyum::world::{
+    MapDocument, MapParseError, WorldMap, is_map_document, is_tiled_map, load_tiled_map,
+    read_map_source,
+};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+const USAGE: &str = "usage: ferrisyum-mapconvert <INPUT> [OUTPUT]";
+
+/// ### MAIN ENTRY POINT
+///
+/// * Converts the map given on the command line
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (input, output) = match args.as_slice() {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let registries = match Registries::load(ITEMS_PATH, TILES_PATH) {
+        Ok(registries) => registries,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let converted = match convert(input, &registries) {
+        Ok(converted) => converted,
+        Err(e) => {
+            eprintln!("error: {}: {}", input, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match output {
+        Some(output) => match std::fs::write(output, converted) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: unable to write {}: {}", output, e);
+                ExitCode::FAILURE
+            }
+        },
+        None => {
+            print!("{}", converted);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Converts the map at `path` to the other format
+fn convert(path: &str, registries: &Registries) -> Result<String, MapParseError> {
+    // Only hand out maps which load
+    let ascii = read_map_source(path, registries)?;
+    WorldMap::parse(ascii.as_bytes(), 0, registries)?;
+
+    if is_map_document(path) {
+        Ok(ascii)
+    } else if is_tiled_map(path) {
+        // Keep the layers of the Tiled map instead of flattening them
+        Ok(load_tiled_map(path, registries)?.to_ron())
+    } else {
+        let name = Path::new(path).file_stem().unwrap_or_default().to_string_lossy();
+        Ok(MapDocument::from_ascii(&ascii, &name, registries)?.to_ron())
+    }
+}
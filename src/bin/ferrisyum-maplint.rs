@@ -0,0 +1,326 @@
+//! # Ferrisyum Map Lint
+//!
+//! Checks map files without launching the game. Every map is parsed exactly as the game would
+//! load it, then checked for solvability, and every problem is printed as a compiler-style
+//! diagnostic pointing at the offending grid tile or `~K` / `~T` / `~M` directive. Lines of the
+//! directive section which were ignored or misplaced are printed as warnings. Structured `.ron`
+//! map documents and Tiled maps are checked too, with diagnostics pointing into the equivalent
+//! ASCII map.
+//!
+//! ```text
+//! ferrisyum-maplint [--start ROW,COL] <MAP>...
+//! ```
+//!
+//! Maps are given ids in the order they are listed, as the game does when loading them. The
+//! player starts the first map at `--start` (0-based) if given, and otherwise enters each map
+//! through the `~M` links of the maps before it or on its `start` spawn point. Maps without a
+//! known start are only checked for format errors.
+//!
+//! Exits with `1` if any error was found, or `2` if the arguments or registries are invalid.
+//! Warnings alone do not fail the lint.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+// > USE CRATE
+use ferrisyum::registry::{Registries, ITEMS_PATH, TILES_PATH};
+use ferrisyum::shared::{MapEntry, Tile, WorldCoordinates};
+use ferrisyum::world::{
+    DEFAULT_SPAWN, MapDirective, SolvabilityIssue, WorldMap, check_solvability, parse_directives,
+    read_map_source,
+};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+const USAGE: &str = "usage: ferrisyum-maplint [--start ROW,COL] <MAP>...";
+
+/// A single problem found in a map file, located by its ***1-based*** line and column
+struct Diagnostic {
+    severity: &'static str,
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+/// ### MAIN ENTRY POINT
+///
+/// * Lints every map given on the command line and reports the problems found
+fn main() -> ExitCode {
+    let (start, paths) = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(reason) => {
+            eprintln!("error: {}\n{}", reason, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let registries = match Registries::load(ITEMS_PATH, TILES_PATH) {
+        Ok(registries) => registries,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    match lint(start, &paths, &registries, &mut io::stdout().lock()) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Lints every map in `paths`, writing the problems found to `out`
+///
+/// # Arguments
+/// * `start` ( `Option<WorldCoordinates>` ) - Where the player starts the first map, if given
+/// * `paths` ( `&[String]` ) - The map files, in the order the game loads them
+/// * `registries` ( `&Registries` ) - The definitions the maps are parsed with
+/// * `out` ( `&mut impl Write` ) - Where the diagnostics and summary are written
+///
+/// ## Returns
+///
+/// The number of errors found
+fn lint(
+    start: Option<WorldCoordinates>,
+    paths: &[String],
+    registries: &Registries,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut entries: Vec<(usize, MapEntry)> =
+        start.map(|s| (0, MapEntry::Coords(s))).into_iter().collect();
+    let mut error_count = 0;
+    let mut warning_count = 0;
+
+    for (map_id, path) in paths.iter().enumerate() {
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        // Diagnostics of structured and Tiled maps point into the map rendered as ASCII
+        let source = read_map_source(path, registries);
+        let loaded = source.as_ref()
+            .map_err(Clone::clone)
+            .and_then(|source| WorldMap::parse(source.as_bytes(), map_id, registries));
+        let source = source.unwrap_or_default();
+
+        let (map, warnings) = match loaded {
+            Ok((map, warnings)) => (Some(map), warnings),
+            Err(e) => {
+                diagnostics.extend(e.errors.iter().map(|kind| Diagnostic {
+                    severity: "error",
+                    message: kind.message(),
+                    line: kind.line(),
+                    column: kind.column(),
+                }));
+                (None, e.warnings)
+            }
+        };
+        let warnings: Vec<Diagnostic> = warnings.iter()
+            .map(|warning| Diagnostic {
+                severity: "warning",
+                message: warning.message(),
+                line: Some(warning.line()),
+                column: None,
+            })
+            .collect();
+
+        if let Some(map) = &map {
+            // Every map link out of this map is a start for the map it leads to
+            entries.extend(map.grid.iter().filter_map(|tile| match tile {
+                Tile::Door(props, _) => {
+                    props.dml.as_ref().map(|dml| (dml.map_id, dml.entry.clone()))
+                }
+                _ => None,
+            }));
+
+            let start = entries.iter()
+                .filter(|(id, _)| *id == map_id)
+                .find_map(|(_, entry)| map.entry_point(entry))
+                .or_else(|| map.spawn(DEFAULT_SPAWN));
+
+            match start {
+                Some(start) => {
+                    if let Err(e) = check_solvability(map, start) {
+                        // The map parsed, so its directives are known to be well-formed
+                        let directives = parse_directives(&source, &mut vec![], &mut vec![]);
+                        diagnostics.extend(e.issues.iter().map(|issue| locate(issue, &directives)));
+                    }
+                }
+                None => writeln!(
+                    out, "note: {}: no start position, skipping solvability checks", path
+                )?,
+            }
+        }
+
+        for diagnostic in diagnostics.iter().chain(&warnings) {
+            write_diagnostic(out, path, &source, diagnostic)?;
+        }
+        error_count += diagnostics.len();
+        warning_count += warnings.len();
+    }
+
+    writeln!(
+        out,
+        "{} map(s) checked, {} error(s) and {} warning(s) found",
+        paths.len(), error_count, warning_count
+    )?;
+    Ok(error_count)
+}
+
+/// Splits the command line into the starting coordinates of the first map and the map paths
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<(Option<WorldCoordinates>, Vec<String>), String> {
+    let mut start = None;
+    let mut paths = vec![];
+
+    while let Some(arg) = args.next() {
+        if arg == "--start" {
+            let value = args.next().ok_or("--start expects ROW,COL")?;
+            let coords = value.split_once(',')
+                .and_then(|(row, col)| Some((row.trim().parse().ok()?, col.trim().parse().ok()?)))
+                .ok_or_else(|| format!("invalid start `{}`, expected ROW,COL", value))?;
+            start = Some(coords);
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option `{}`", arg));
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    if paths.is_empty() {
+        return Err("no map files given".to_string());
+    }
+    Ok((start, paths))
+}
+
+/// Points a [SolvabilityIssue] at the directive describing its tile, or at the tile itself if
+/// there is no such directive
+///
+/// # Arguments
+/// * `issue` ( `&SolvabilityIssue` ) - The issue to locate
+/// * `directives` ( `&[(usize, MapDirective)]` ) - The map's directives, paired with the
+///   ***1-based*** line each one was defined on
+fn locate(issue: &SolvabilityIssue, directives: &[(usize, MapDirective)]) -> Diagnostic {
+    let directive = directives.iter().find(|(_, directive)| match (issue, directive) {
+        (SolvabilityIssue::InvalidStart { start }, MapDirective::Spawn { coords, .. }) => {
+            coords == start
+        }
+        (SolvabilityIssue::UnreachableChest { chest }, MapDirective::Treasure { coords, .. }) => {
+            coords == chest
+        }
+        (SolvabilityIssue::UnreachableDoor { door }, MapDirective::DoorMap(dml)) => {
+            dml.door_coords == *door
+        }
+        (SolvabilityIssue::UnreachableDoor { door }, MapDirective::KeyDoor(kdl)) => {
+            kdl.door_coords == *door
+        }
+        (
+            SolvabilityIssue::UnreachableKey { key, .. }
+            | SolvabilityIssue::KeyBehindOwnDoor { key, .. }
+            | SolvabilityIssue::Deadlock { key, .. },
+            MapDirective::KeyDoor(kdl),
+        ) => kdl.key_coords == *key,
+        _ => false,
+    });
+
+    let (row, col) = issue.coords();
+    match directive {
+        Some((line, _)) => Diagnostic {
+            severity: "error", message: issue.message(), line: Some(*line), column: Some(1),
+        },
+        None => Diagnostic {
+            severity: "error", message: issue.message(), line: Some(row + 1), column: Some(col + 1),
+        },
+    }
+}
+
+/// Writes a [Diagnostic] with the source line it points at
+fn write_diagnostic(
+    out: &mut impl Write,
+    path: &str,
+    source: &str,
+    diagnostic: &Diagnostic,
+) -> io::Result<()> {
+    writeln!(out, "{}: {}", diagnostic.severity, diagnostic.message)?;
+
+    let Some(line) = diagnostic.line else {
+        return writeln!(out, " --> {}\n", path);
+    };
+    let gutter = " ".repeat(line.to_string().len());
+    match diagnostic.column {
+        Some(column) => writeln!(out, "{}--> {}:{}:{}", gutter, path, line, column)?,
+        None => writeln!(out, "{}--> {}:{}", gutter, path, line)?,
+    }
+
+    if let Some(text) = source.lines().nth(line - 1) {
+        writeln!(out, "{} |", gutter)?;
+        writeln!(out, "{} | {}", line, text)?;
+        if let Some(column) = diagnostic.column {
+            writeln!(out, "{} | {}^", gutter, " ".repeat(column - 1))?;
+        }
+    }
+    writeln!(out)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The key at (1,5) is walled off, and its directive is spaced out
+    const WALLED_KEY_MAP: &str = "\
+#######
+#.|.#K#
+#######
+~[keys]
+~K( 1, 5 ) = D( 1, 2 )
+~[spawns]
+~S(1,1) = start
+";
+
+    /// Lints a single map with the built-in definitions
+    ///
+    /// ## Returns
+    ///
+    /// The number of errors found and everything written
+    fn lint_map(name: &str, contents: &str) -> (usize, String, String) {
+        let path = std::env::temp_dir()
+            .join(format!("ferrisyum-maplint-{}-{}.txt", std::process::id(), name))
+            .display()
+            .to_string();
+        std::fs::write(&path, contents).unwrap();
+
+        let mut out = vec![];
+        let errors = lint(None, std::slice::from_ref(&path), &Registries::default(), &mut out);
+        std::fs::remove_file(&path).unwrap();
+        (errors.unwrap(), String::from_utf8(out).unwrap(), path)
+    }
+
+    #[test]
+    fn issues_point_at_their_directive_line() {
+        let (errors, out, path) = lint_map("walled-key", WALLED_KEY_MAP);
+        let issue = SolvabilityIssue::UnreachableKey { key: (1, 5), door: (1, 2) };
+
+        assert_eq!(errors, 1);
+        assert_eq!(out, format!(
+            "error: {}\n --> {}:5:1\n  |\n5 | ~K( 1, 5 ) = D( 1, 2 )\n  | ^\n\n\
+            1 map(s) checked, 1 error(s) and 0 warning(s) found\n",
+            issue.message(), path
+        ));
+    }
+
+    #[test]
+    fn clean_maps_report_no_errors() {
+        let (errors, out, _) = lint_map("clean", "#####\n#...#\n#####\n~S(1,1) = start\n");
+
+        assert_eq!(errors, 0);
+        assert_eq!(out, "1 map(s) checked, 0 error(s) and 0 warning(s) found\n");
+    }
+}
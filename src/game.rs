@@ -0,0 +1,323 @@
+//! # Game
+//!
+//! A headless container for the full game state. The `Game` owns the [Player] and the
+//! [WorldController] (which owns the world update queue), and advances by applying abstract
+//! [GameAction]s and simulation [ticks](Game::tick). It does not depend on a terminal, so the
+//! same type drives both the interactive game loop and scripted playthrough tests.
+//!
+//! Every [WorldEvent] is recorded into the session's [MessageLog], along with messages for the
+//! actions the world doesn't see (drinking potions, equipping items, saving and loading).
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::cell::{Ref, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+// > CRATE
+use crate::message_log::MessageLog;
+use crate::registry::Registries;
+use crate::save::{self, SaveError};
+use crate::shared::extlib::RatatuiFrame;
+use crate::shared::MoveDirection;
+use crate::shared::treasure::TreasureType;
+use crate::ui::{self, UiState};
+use crate::world::{
+    self, ExplicitPickupType, ListenerId, MapParseError, TimeMode, WorldController, WorldEvent,
+};
+use crate::Player;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` of the abstract actions which advance the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameAction {
+    Move(MoveDirection),
+    PickupTreasure,
+    UsePotion,
+    EquipArmor,
+    EquipWeapon,
+    ToggleInventory,
+    ToggleStats,
+    ToggleMessages,
+    ScrollMessagesUp,
+    ScrollMessagesDown,
+    QuickSave,
+    QuickLoad,
+    Quit,
+}
+
+impl GameAction {
+    /// Whether the action spends the player's turn, after which every NPC takes its turn in
+    /// [TimeMode::TurnBased]
+    pub fn takes_turn(&self) -> bool {
+        matches!(
+            self,
+            GameAction::Move(_)
+                | GameAction::PickupTreasure
+                | GameAction::UsePotion
+                | GameAction::EquipArmor
+                | GameAction::EquipWeapon
+        )
+    }
+}
+
+/// An `enum` indicating whether the game should keep running after an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Running,
+    /// The player has died; only actions which do not take a turn are still applied
+    GameOver,
+    Quit,
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Owns the full game state and applies [GameAction]s to it
+pub struct Game {
+    player: Player,
+    world: WorldController,
+    /// Shared with the world listener which records every event
+    log: Rc<RefCell<MessageLog>>,
+    /// Where [GameAction::QuickSave] and [GameAction::QuickLoad] write and read the game
+    quicksave_path: PathBuf,
+    pub ui: UiState,
+}
+
+impl Game {
+    /// Creates a new `Game` with no maps loaded and the built-in definitions
+    ///
+    /// # Arguments
+    /// * `player` ( `Player` ) - The player, placed at its starting position or later with
+    ///   [Game::spawn_player]
+    pub fn new(player: Player) -> Self {
+        Self::with_registries(player, Registries::default())
+    }
+
+    /// Creates a new `Game` with no maps loaded, using the given definitions
+    ///
+    /// # Arguments
+    /// * `player` ( `Player` ) - The player, placed at its starting position or later with
+    ///   [Game::spawn_player]
+    /// * `registries` ( `Registries` ) - The definitions of every tile and item in the game
+    pub fn with_registries(player: Player, registries: Registries) -> Self {
+        let mut world = WorldController::with_registries(registries);
+        let log = Rc::new(RefCell::new(MessageLog::default()));
+
+        let listener_log = Rc::clone(&log);
+        world.subscribe(move |event, world, _| listener_log.borrow_mut().record(event, world));
+
+        Self {
+            player,
+            world,
+            log,
+            quicksave_path: PathBuf::from(save::QUICKSAVE_PATH),
+            ui: UiState::default(),
+        }
+    }
+
+    /// Loads a map file into the world (see
+    /// [WorldController::load_map_from_fstr](crate::world::WorldController::load_map_from_fstr))
+    pub fn load_map(&mut self, map_file: &str) -> Result<(), MapParseError> {
+        self.world.load_map_from_fstr(map_file)
+    }
+
+    /// Places the player on a spawn point of a loaded map (see
+    /// [WorldController::spawn_player](crate::world::WorldController::spawn_player))
+    pub fn spawn_player(&mut self, map_id: usize, spawn: &str) -> bool {
+        self.world.spawn_player(&mut self.player, map_id, spawn)
+    }
+
+    /// Applies a single [GameAction] and processes the resulting world updates
+    ///
+    /// # Arguments
+    /// * `action` ( `GameAction` ) - The action to apply
+    ///
+    /// # Returns
+    /// [GameStatus::Quit] if the action ends the game, [GameStatus::GameOver] while the player is
+    /// dead, otherwise [GameStatus::Running]. A failed quicksave or quickload is logged and
+    /// returned as an `Err`, leaving the game as it was.
+    pub fn apply(&mut self, action: GameAction) -> Result<GameStatus, SaveError> {
+        if action.takes_turn() && self.player.stats.is_dead() {
+            return Ok(GameStatus::GameOver);
+        }
+
+        match action {
+            GameAction::Move(direction) => {
+                world::translate(&mut self.player, &mut self.world, direction);
+            }
+            GameAction::PickupTreasure => {
+                world::pickup_explicit(
+                    &mut self.player, &mut self.world, ExplicitPickupType::TreasureChest
+                );
+            }
+            GameAction::UsePotion => {
+                let items = &self.world.registries.items;
+                let message = match self.player.use_potion(items) {
+                    Some(id) => format!("You drink the {}.", items.name(id)),
+                    None => "You have no potions.".to_string(),
+                };
+                self.log.borrow_mut().push(message);
+            }
+            GameAction::EquipArmor => self.equip(TreasureType::Armor),
+            GameAction::EquipWeapon => self.equip(TreasureType::Weapon),
+            GameAction::ToggleInventory => self.ui.show_inventory = !self.ui.show_inventory,
+            GameAction::ToggleStats => self.ui.show_stats = !self.ui.show_stats,
+            GameAction::ToggleMessages => self.ui.show_messages = !self.ui.show_messages,
+            GameAction::ScrollMessagesUp => self.ui.message_scroll += 1,
+            GameAction::ScrollMessagesDown => {
+                self.ui.message_scroll = self.ui.message_scroll.saturating_sub(1);
+            }
+            GameAction::QuickSave => {
+                save::save_game(&self.quicksave_path, &self.player, &self.world)
+                    .inspect_err(|e| self.log.borrow_mut().push(format!("Save failed: {}", e)))?;
+                self.log.borrow_mut().push("Game saved.");
+            }
+            GameAction::QuickLoad => {
+                self.player = save::load_game(&self.quicksave_path, &mut self.world)
+                    .inspect_err(|e| self.log.borrow_mut().push(format!("Load failed: {}", e)))?;
+                self.log.borrow_mut().push("Game loaded.");
+            }
+            GameAction::Quit => return Ok(GameStatus::Quit),
+        }
+
+        self.world.update_world(&mut self.player);
+        if action.takes_turn() && self.world.time_mode == TimeMode::TurnBased {
+            world::npc_turns(&mut self.world, &self.player);
+            self.world.update_world(&mut self.player);
+        }
+
+        Ok(self.status())
+    }
+
+    /// Advances the world by one simulation tick (see
+    /// [WorldController::tick](crate::world::WorldController::tick)). The world stops once the
+    /// player has died.
+    ///
+    /// # Returns
+    /// [GameStatus::GameOver] while the player is dead, otherwise [GameStatus::Running]
+    pub fn tick(&mut self) -> GameStatus {
+        if !self.player.stats.is_dead() {
+            self.world.tick(&mut self.player);
+        }
+        self.status()
+    }
+
+    /// Advances the world by `ticks` simulation ticks (see [Game::tick])
+    ///
+    /// # Arguments
+    /// * `ticks` ( `u64` ) - The number of ticks to advance
+    pub fn advance(&mut self, ticks: u64) -> GameStatus {
+        for _ in 0..ticks {
+            self.tick();
+        }
+        self.status()
+    }
+
+    /// Registers a listener for every [WorldEvent] (see
+    /// [WorldController::subscribe](crate::world::WorldController::subscribe))
+    pub fn subscribe(
+        &mut self,
+        listener: impl FnMut(&WorldEvent, &mut WorldController, &Player) + 'static,
+    ) -> ListenerId {
+        self.world.subscribe(listener)
+    }
+
+    /// Removes a listener registered with [Game::subscribe] (see
+    /// [WorldController::unsubscribe](crate::world::WorldController::unsubscribe))
+    pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+        self.world.unsubscribe(id)
+    }
+
+    /// Changes where quicksaves are written and read
+    ///
+    /// # Arguments
+    /// * `path` ( `impl Into<PathBuf>` ) - The path of the quicksave file
+    pub fn set_quicksave_path(&mut self, path: impl Into<PathBuf>) {
+        self.quicksave_path = path.into();
+    }
+
+    /// Switches between turn-based and real-time NPC turns
+    ///
+    /// # Arguments
+    /// * `mode` ( `TimeMode` ) - When NPCs take their turns from now on
+    pub fn set_time_mode(&mut self, mode: TimeMode) {
+        self.world.time_mode = mode;
+    }
+
+    /// Equips the first carried item of a category and logs the outcome
+    ///
+    /// # Arguments
+    /// * `category` ( `TreasureType` ) - Either `Armor` or `Weapon`
+    fn equip(&mut self, category: TreasureType) {
+        let items = &self.world.registries.items;
+        let message = if self.player.equip(category, items) {
+            let slot = match category {
+                TreasureType::Armor => self.player.equipment.armor,
+                _ => self.player.equipment.weapon,
+            };
+            format!("You equip the {}.", slot.map_or("item", |id| items.name(id)))
+        } else {
+            "You have nothing to equip.".to_string()
+        };
+        self.log.borrow_mut().push(message);
+    }
+
+    /// Whether the player is still alive
+    fn status(&self) -> GameStatus {
+        if self.player.stats.is_dead() {
+            GameStatus::GameOver
+        } else {
+            GameStatus::Running
+        }
+    }
+
+    /// Applies a sequence of [GameAction]s in order, stopping early if one of them quits
+    ///
+    /// # Arguments
+    /// * `actions` ( `impl IntoIterator<Item = GameAction>` ) - The actions to apply
+    ///
+    /// # Returns
+    /// [GameStatus::Quit] if one of the actions quit, [GameStatus::GameOver] if the player died
+    /// along the way, otherwise [GameStatus::Running]
+    pub fn run(
+        &mut self,
+        actions: impl IntoIterator<Item = GameAction>
+    ) -> Result<GameStatus, SaveError> {
+        for action in actions {
+            if self.apply(action)? == GameStatus::Quit {
+                return Ok(GameStatus::Quit);
+            }
+        }
+        Ok(self.status())
+    }
+
+    /// Renders the game into the `frame` (see [ui::draw])
+    pub fn draw(&mut self, frame: &mut RatatuiFrame) {
+        ui::draw(frame, &self.player, &self.world, &self.log.borrow(), &mut self.ui);
+    }
+
+    /// ## Returns
+    ///
+    /// The messages logged so far this session
+    pub fn messages(&self) -> Ref<'_, MessageLog> {
+        self.log.borrow()
+    }
+
+    /// ## Returns
+    ///
+    /// An ***immutable*** reference to the player
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    /// ## Returns
+    ///
+    /// An ***immutable*** reference to the world controller
+    pub fn world(&self) -> &WorldController {
+        &self.world
+    }
+}
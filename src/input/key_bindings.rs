@@ -0,0 +1,352 @@
+//! # Key Bindings
+//!
+//! Maps keys (including modifiers) to [GameAction]s. The keybinding config format is one binding
+//! per line:
+//!
+//! ```text
+//! # comment
+//! action_name = key[, key ...]
+//! ```
+//!
+//! where a key is a single character or a key name (`Up`, `Esc`, `Space`, `PageUp`, `F5`, ...),
+//! optionally prefixed by modifiers (`ctrl+`, `alt+`, `shift+`). An action listed in the config
+//! replaces ***all*** of its default keys.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+// > USE 3P
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+// > CRATE
+use crate::game::GameAction;
+use crate::shared::MoveDirection;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The built-in bindings, written in the keybinding config format
+const DEFAULT_BINDINGS: &str = include_str!("../../assets/keybindings.txt");
+
+/// The config names of every bindable [GameAction]
+const ACTION_NAMES: [(&str, GameAction); 16] = [
+    ("move_up", GameAction::Move(MoveDirection::UP)),
+    ("move_down", GameAction::Move(MoveDirection::DOWN)),
+    ("move_left", GameAction::Move(MoveDirection::LEFT)),
+    ("move_right", GameAction::Move(MoveDirection::RIGHT)),
+    ("pickup", GameAction::PickupTreasure),
+    ("use_potion", GameAction::UsePotion),
+    ("equip_armor", GameAction::EquipArmor),
+    ("equip_weapon", GameAction::EquipWeapon),
+    ("toggle_inventory", GameAction::ToggleInventory),
+    ("toggle_stats", GameAction::ToggleStats),
+    ("toggle_messages", GameAction::ToggleMessages),
+    ("scroll_messages_up", GameAction::ScrollMessagesUp),
+    ("scroll_messages_down", GameAction::ScrollMessagesDown),
+    ("quick_save", GameAction::QuickSave),
+    ("quick_load", GameAction::QuickLoad),
+    ("quit", GameAction::Quit),
+];
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A key and its modifiers which can be bound to a [GameAction]
+///
+/// `SHIFT` is never stored for character keys, as the character itself already carries its case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Creates a new, normalized `KeyBinding`
+    ///
+    /// # Arguments
+    /// * `code` ( `KeyCode` ) - The key
+    /// * `modifiers` ( `KeyModifiers` ) - The modifiers held with the key
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parses a `KeyBinding` from its config representation (e.g. `w`, `PageUp`, `ctrl+s`)
+    pub fn parse(key: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut name = key.trim();
+
+        // Consume modifier prefixes, leaving the key name (which may itself be `+`)
+        while let Some((prefix, rest)) = name.split_once('+')
+            && !rest.is_empty()
+        {
+            modifiers |= match prefix.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            name = rest.trim();
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next()?, chars.next()) {
+            (c, None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (c, None) => KeyCode::Char(c),
+            _ => parse_key_name(name)?,
+        };
+
+        Some(Self::new(code, modifiers))
+    }
+}
+
+/// Parses a named (multi-character) key such as `Esc` or `F5`
+fn parse_key_name(name: &str) -> Option<KeyCode> {
+    let lower = name.to_ascii_lowercase();
+    let code = match lower.as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => match lower.strip_prefix('f')?.parse::<u8>().ok()? {
+            n @ 1..=12 => KeyCode::F(n),
+            _ => return None,
+        },
+    };
+    Some(code)
+}
+
+/// Looks up a [GameAction] by its config name
+fn action_from_name(name: &str) -> Option<GameAction> {
+    ACTION_NAMES.iter().find(|(n, _)| *n == name).map(|(_, action)| *action)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing the ways loading a keybinding config can fail. Line numbers are
+/// ***1-based***.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyBindingError {
+    /// The config file exists but could not be read
+    Unreadable { path: String, reason: String },
+    /// A line is not of the form `action = key[, key ...]`
+    Malformed { line: usize, text: String },
+    /// A line names an action that does not exist
+    UnknownAction { line: usize, action: String },
+    /// A line names a key that could not be parsed
+    UnknownKey { line: usize, key: String },
+}
+
+impl fmt::Display for KeyBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable { path, reason } => {
+                write!(f, "unable to read keybinding file {}: {}", path, reason)
+            }
+            Self::Malformed { line, text } => {
+                write!(f, "{}: malformed keybinding `{}`", line, text)
+            }
+            Self::UnknownAction { line, action } => {
+                write!(f, "{}: unknown action `{}`", line, action)
+            }
+            Self::UnknownKey { line, key } => write!(f, "{}: unknown key `{}`", line, key),
+        }
+    }
+}
+
+impl std::error::Error for KeyBindingError {}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The active mapping of [KeyBinding]s to [GameAction]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    bindings: HashMap<KeyBinding, GameAction>,
+}
+
+// IMPL Default
+impl Default for KeyBindings {
+    /// Returns the built-in bindings
+    fn default() -> Self {
+        let mut bindings = Self { bindings: HashMap::new() };
+        bindings.apply_config(DEFAULT_BINDINGS).expect("Default key bindings must be valid.");
+        bindings
+    }
+}
+
+impl KeyBindings {
+    /// Loads the bindings from the config file at `path`, using the defaults for every action the
+    /// file does not mention. A missing file yields the defaults.
+    ///
+    /// # Arguments
+    /// * `path` ( `impl AsRef<Path>` ) - The path of the keybinding config file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeyBindingError> {
+        match std::fs::read_to_string(path.as_ref()) {
+            Ok(contents) => Self::from_config(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(KeyBindingError::Unreadable {
+                path: path.as_ref().display().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    /// Parses the bindings from the contents of a keybinding config, using the defaults for every
+    /// action the config does not mention
+    pub fn from_config(contents: &str) -> Result<Self, KeyBindingError> {
+        let mut bindings = Self::default();
+        bindings.apply_config(contents)?;
+        Ok(bindings)
+    }
+
+    /// Binds `binding` to `action`, replacing whatever it was previously bound to
+    pub fn bind(&mut self, binding: KeyBinding, action: GameAction) {
+        self.bindings.insert(binding, action);
+    }
+
+    /// ## Returns
+    ///
+    /// The [GameAction] bound to the key event, or `None` if the key is unbound or the event is
+    /// not a key press
+    pub fn action_for(&self, event: &KeyEvent) -> Option<GameAction> {
+        if event.kind != KeyEventKind::Press {
+            return None;
+        }
+        self.bindings.get(&KeyBinding::new(event.code, event.modifiers)).copied()
+    }
+
+    /// Applies every line of a keybinding config, replacing the existing keys of each action the
+    /// config mentions
+    fn apply_config(&mut self, contents: &str) -> Result<(), KeyBindingError> {
+        let mut rebound: Vec<GameAction> = vec![];
+
+        for (idx, text) in contents.lines().enumerate() {
+            let line = idx + 1;
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let Some((name, keys)) = text.split_once('=') else {
+                return Err(KeyBindingError::Malformed { line, text: text.to_string() });
+            };
+            let name = name.trim();
+            let action = action_from_name(name).ok_or_else(|| {
+                KeyBindingError::UnknownAction { line, action: name.to_string() }
+            })?;
+
+            if !rebound.contains(&action) {
+                self.bindings.retain(|_, bound| *bound != action);
+                rebound.push(action);
+            }
+
+            for key in keys.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+                let binding = KeyBinding::parse(key).ok_or_else(|| {
+                    KeyBindingError::UnknownKey { line, key: key.to_string() }
+                })?;
+                self.bind(binding, action);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A key press of `code` with `modifiers` held
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_key_specs() {
+        assert_eq!(
+            KeyBinding::parse("w"),
+            Some(KeyBinding::new(KeyCode::Char('w'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            KeyBinding::parse("ctrl+c"),
+            Some(KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyBinding::parse("Ctrl + Alt + PageUp"),
+            Some(KeyBinding::new(KeyCode::PageUp, KeyModifiers::CONTROL | KeyModifiers::ALT))
+        );
+        assert_eq!(KeyBinding::parse("PageDown").map(|b| b.code), Some(KeyCode::PageDown));
+        assert_eq!(KeyBinding::parse("F12").map(|b| b.code), Some(KeyCode::F(12)));
+        assert_eq!(KeyBinding::parse("Space").map(|b| b.code), Some(KeyCode::Char(' ')));
+
+        // Shifted characters are stored as the character they type
+        assert_eq!(KeyBinding::parse("shift+a"), KeyBinding::parse("A"));
+        assert_eq!(KeyBinding::parse("ctrl++").map(|b| b.code), Some(KeyCode::Char('+')));
+
+        assert_eq!(KeyBinding::parse("F13"), None);
+        assert_eq!(KeyBinding::parse("PageSideways"), None);
+        assert_eq!(KeyBinding::parse(""), None);
+    }
+
+    #[test]
+    fn defaults_match_the_bundled_config() {
+        let bindings = KeyBindings::default();
+
+        let action = |code, modifiers| bindings.action_for(&press(code, modifiers));
+
+        assert_eq!(action(KeyCode::PageUp, KeyModifiers::NONE), Some(GameAction::ScrollMessagesUp));
+        assert_eq!(action(KeyCode::Char('c'), KeyModifiers::CONTROL), Some(GameAction::Quit));
+        assert_eq!(action(KeyCode::Char('c'), KeyModifiers::NONE), Some(GameAction::ToggleStats));
+    }
+
+    #[test]
+    fn config_lists_several_keys_per_action() {
+        let bindings = KeyBindings::from_config("# arrows only\nmove_up = Up, k\n").unwrap();
+
+        let action = |code| bindings.action_for(&press(code, KeyModifiers::NONE));
+
+        assert_eq!(action(KeyCode::Up), Some(GameAction::Move(MoveDirection::UP)));
+        assert_eq!(action(KeyCode::Char('k')), Some(GameAction::Move(MoveDirection::UP)));
+        // Listing an action replaces its default keys, leaving the other actions alone
+        assert_eq!(action(KeyCode::Char('w')), None);
+        assert_eq!(action(KeyCode::Down), Some(GameAction::Move(MoveDirection::DOWN)));
+    }
+
+    #[test]
+    fn config_errors_report_their_line() {
+        assert_eq!(
+            KeyBindings::from_config("pickup = Space\njump = j\n"),
+            Err(KeyBindingError::UnknownAction { line: 2, action: "jump".to_string() })
+        );
+        assert_eq!(
+            KeyBindings::from_config("\nquit = q, hyper+q\n"),
+            Err(KeyBindingError::UnknownKey { line: 2, key: "hyper+q".to_string() })
+        );
+        assert_eq!(
+            KeyBindings::from_config("quit q"),
+            Err(KeyBindingError::Malformed { line: 1, text: "quit q".to_string() })
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! # *mod* Input
+//!
+//! Translates crossterm `KeyEvent`s into abstract [GameAction](crate::game::GameAction)s using
+//! rebindable [KeyBindings]. Bindings are loaded from a keybinding config file, falling back to
+//! the built-in defaults for any action the file does not mention.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// >> CRATE (RE-EXPORT)
+mod key_bindings;
+pub use key_bindings::*;
+
+/// The default path of the keybinding config file
+pub const KEYBINDINGS_PATH: &str = "assets/keybindings.txt";
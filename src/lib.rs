@@ -0,0 +1,26 @@
+//! # Ferrisyum
+//!
+//! The game engine library. The `Ferrisyum` binary drives it through a terminal, while tests and
+//! tools use the same types headlessly.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// # GLOBAL LINT FLAGS
+// #
+#![allow(dead_code, unused_variables, unused_imports, unused_braces)]
+
+// < MOD
+mod player;
+pub use player::Player;
+
+pub mod game;
+pub mod input;
+pub mod message_log;
+pub mod npc;
+pub mod registry;
+pub mod save;
+pub mod shared;
+pub mod ui;
+pub mod world;
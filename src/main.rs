@@ -1,32 +1,25 @@
 // # GLOBAL LINT FLAGS
 // #
 #![allow(dead_code, unused_variables, unused_imports, unused_braces)]
-#![feature(file_buffered)]
 
-// > USE STD
-use std::collections::BinaryHeap;
+// > USE
+use std::time::Instant;
 
 // > USE 3P
+use color_eyre::eyre::eyre;
 use color_eyre::Result as CEResult;
-use ratatui::{
-    layout::{Layout, Direction, Constraint},
-    text::Text,
-    widgets::{Block, Borders, Padding, Paragraph}
-};
-
-// < MOD
-mod player;
-pub(crate) use player::Player;
 
-mod shared;
-use shared::extlib::{
-    CrosstermEvent, CrosstermKeyCode, crossterm_event,
+// > USE CRATE
+use ferrisyum::game::{Game, GameStatus};
+use ferrisyum::input::{KeyBindings, KEYBINDINGS_PATH};
+use ferrisyum::registry::{Registries, ITEMS_PATH, TILES_PATH};
+use ferrisyum::shared::constants::{FRAME_DURATION, MAX_CATCH_UP_TICKS, TICK_DURATION};
+use ferrisyum::world::{DEFAULT_SPAWN, TimeMode};
+use ferrisyum::shared::extlib::{
+    CrosstermEvent, crossterm_event,
     NAVector3, RatatuiDefaultTerminal,
 };
-use shared::MoveDirection;
-
-mod world;
-use world::{WorldController, WorldView, WorldUpdate, WorldUpdateEventType, ExplicitPickupType};
+use ferrisyum::Player;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -44,120 +37,66 @@ fn main() -> CEResult<()> {
 /// ### INIT GAME LOOP
 ///
 /// * Sets up the terminal, initializes the render context, and handles key input
+/// * Advances the simulation at a fixed rate of one tick per `TICK_DURATION`, renders at most
+///   once per `FRAME_DURATION`, and polls for input in between so the world keeps moving while
+///   the player is idle
+/// * NPCs act in real time unless the game is started with `--turn-based`
 fn init_game_loop(mut terminal: RatatuiDefaultTerminal) -> CEResult<()> {
+    let key_bindings = KeyBindings::load(KEYBINDINGS_PATH)?;
+    let registries = Registries::load(ITEMS_PATH, TILES_PATH)?;
+    let mut game = Game::with_registries(Player::new('@', NAVector3::new(0, 0, 1)), registries);
 
-    let mut world_update_queue: BinaryHeap<WorldUpdate<WorldUpdateEventType>> 
-        = BinaryHeap::new();
+    // Load maps
+    game.load_map("assets/test_map1.txt")?;
+    game.load_map("assets/test_map2.txt")?;
+    if !game.spawn_player(0, DEFAULT_SPAWN) {
+        return Err(eyre!("the first map has no `{}` spawn point", DEFAULT_SPAWN));
+    }
 
-    let mut player = Player::new('@', NAVector3::new(2, 1, 1));
-    let mut world = WorldController::new(&mut world_update_queue);
-    let mut show_stats = true;
-    let mut show_inventory = true;
+    if std::env::args().skip(1).any(|arg| arg == "--turn-based") {
+        game.set_time_mode(TimeMode::TurnBased);
+    } else {
+        game.set_time_mode(TimeMode::RealTime);
+    }
 
-    // Load maps
-    world.load_map_from_fstr("assets/test_map1.txt");
-    
     // Start game loop
+    let mut last_tick = Instant::now();
+    let mut last_frame: Option<Instant> = None;
     loop {
-        // TODO: THIS NEEDS TO BE MOVED INTO A UI/INPUT HANDLING MODULE ///////////////////////////
-        terminal.draw(|f| {
-            let vertical_chunks = if show_stats {
-                Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Min(30),
-                        Constraint::Percentage(30), 
-                    ])
-                    .split(f.area())
-            } else {
-                // Full screen
-                Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Min(0),
-                    ])
-                    .split(f.area())
-            };
-
-            let horizontal_chunks = if show_inventory {
-                Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([
-                        Constraint::Percentage(70), 
-                        Constraint::Percentage(30), 
-                    ])
-                    .split(vertical_chunks[0])
-            } else {
-                // Full screen
-                Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([
-                        Constraint::Min(0),
-                    ])
-                    .split(f.area())
-            };
-
-            // Create the WorldView which will handle rendering the map
-            let world_view = WorldView::new(&world, &player);
-
-            // Render the WorldView in the game area
-            f.render_widget(world_view, horizontal_chunks[0]);
-
-            // Render inventory if visible
-            if show_inventory {
-                f.render_widget(
-                    Block::default()
-                        .title("Inventory")
-                        .borders(Borders::ALL), 
-                    horizontal_chunks[1]
-                );
+        // Run every tick which has fallen due since the last iteration
+        let mut caught_up = 0;
+        while last_tick.elapsed() >= TICK_DURATION {
+            game.tick();
+            last_tick += TICK_DURATION;
+            caught_up += 1;
+
+            if caught_up == MAX_CATCH_UP_TICKS {
+                last_tick = Instant::now();
             }
-                
-            // Render stats if visible
-            if show_stats {
-                let stats_block = Block::default()
-                    .title("Stats")
-                    .borders(Borders::ALL)
-                    .padding(Padding::new(1, 1, 1, 1));
+        }
+
+        if last_frame.is_none_or(|frame| frame.elapsed() >= FRAME_DURATION) {
+            terminal.draw(|f| game.draw(f))?;
+            last_frame = Some(Instant::now());
+        }
 
-                let gold_text = Paragraph::new(Text::raw(format!("Gold: {}", player.gold_qty())))
-                    .block(stats_block);
+        // Wait for input until the next tick or frame is due
+        let until_tick = TICK_DURATION.saturating_sub(last_tick.elapsed());
+        let until_frame = last_frame
+            .map_or(FRAME_DURATION, |frame| FRAME_DURATION.saturating_sub(frame.elapsed()));
+        if !crossterm_event::poll(until_tick.min(until_frame))? {
+            continue;
+        }
 
-                f.render_widget(gold_text, vertical_chunks[1]);
+        if let CrosstermEvent::Key(key_event) = crossterm_event::read()?
+            && let Some(action) = key_bindings.action_for(&key_event)
+        {
+            // A failed quicksave or quickload is already in the message log; keep playing
+            if let Ok(GameStatus::Quit) = game.apply(action) {
+                break Ok(());
             }
-        })?;
-        ////////////////////////////////////////////////////////////////////////////////////// TODO
-        
-        if let CrosstermEvent::Key(key_event) = crossterm_event::read()? {
-            match key_event.code {
-                CrosstermKeyCode::Char('w') | CrosstermKeyCode::Up => {
-                    world::translate(&mut player, &mut world, MoveDirection::UP);
-                }
-                CrosstermKeyCode::Char('a') | CrosstermKeyCode::Left => {
-                    world::translate(&mut player, &mut world, MoveDirection::LEFT);
-                }
-                CrosstermKeyCode::Char('s') | CrosstermKeyCode::Down => {
-                    world::translate(&mut player, &mut world, MoveDirection::DOWN);
-                }
-                CrosstermKeyCode::Char('d') | CrosstermKeyCode::Right => {
-                    world::translate(&mut player, &mut world, MoveDirection::RIGHT);
-                }
-                CrosstermKeyCode::Char('i') => {
-                    show_inventory = !show_inventory;
-                },
-                CrosstermKeyCode::Char('c') => {
-                    show_stats = !show_stats;
-                }
-                CrosstermKeyCode::Char(' ') => {
-                    world::pickup_explicit(
-                        &mut player, &mut world, ExplicitPickupType::TreasureChest
-                    );
-                }
-                CrosstermKeyCode::Char('q') | CrosstermKeyCode::Esc => break Ok(()),
-                _ => (),
-            }
-
-            world.update_world(&mut player);
+            // Show the result of the action without waiting for the next frame
+            last_frame = None;
         }
     }
 }
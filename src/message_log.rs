@@ -0,0 +1,205 @@
+//! # Message Log
+//!
+//! The history of messages shown to the player in the messages panel. Most messages describe
+//! [WorldEvent]s ("The door is locked.", "You found 500 gold."); the [Game](crate::game::Game)
+//! records every event it hears and adds messages of its own for actions outside of the world,
+//! such as saving.
+//!
+//! The log lives for the whole session: loading a save keeps the history, and only the oldest
+//! messages are dropped once `MESSAGE_LOG_CAPACITY` is reached.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::VecDeque;
+
+// > CRATE
+use crate::registry::ItemRegistry;
+use crate::shared::constants::MESSAGE_LOG_CAPACITY;
+use crate::shared::treasure::{GOLD_ID, TreasureID, TreasureQuantity};
+use crate::world::{Combatant, WorldController, WorldEvent};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A single entry of the [MessageLog]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub text: String,
+    /// How many times in a row the message was logged
+    pub repeats: u32,
+}
+
+impl Message {
+    /// ## Returns
+    ///
+    /// The text of the message, followed by its repeat count if it was logged more than once
+    pub fn display(&self) -> String {
+        match self.repeats {
+            1 => self.text.clone(),
+            repeats => format!("{} (x{})", self.text, repeats),
+        }
+    }
+}
+
+/// The messages shown to the player, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageLog {
+    messages: VecDeque<Message>,
+}
+
+impl MessageLog {
+    /// Adds a message to the end of the log. A message equal to the last one only increases the
+    /// last one's repeat count
+    ///
+    /// # Arguments
+    /// * `text` ( `impl Into<String>` ) - The text of the message
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if let Some(last) = self.messages.back_mut()
+            && last.text == text
+        {
+            last.repeats += 1;
+            return;
+        }
+
+        if self.messages.len() == MESSAGE_LOG_CAPACITY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message { text, repeats: 1 });
+    }
+
+    /// Adds the message describing a [WorldEvent], if the player should hear about it
+    ///
+    /// # Arguments
+    /// * `event` ( `&WorldEvent` ) - The event which just happened
+    /// * `world` ( `&WorldController` ) - The world the event happened in, used to name the items
+    ///   and NPCs involved
+    pub fn record(&mut self, event: &WorldEvent, world: &WorldController) {
+        if let Some(text) = describe(event, world) {
+            self.push(text);
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// An iterator over every message, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Message> + ExactSizeIterator {
+        self.messages.iter()
+    }
+
+    /// ## Returns
+    ///
+    /// The number of messages in the log
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if nothing has been logged yet
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// ## Returns
+///
+/// The message telling the player about an event, or `None` for events they shouldn't hear about
+fn describe(event: &WorldEvent, world: &WorldController) -> Option<String> {
+    let npc_name = |combatant: &Combatant| match combatant {
+        Combatant::Npc(coords) => world.active_map().npc_at(*coords)
+            .map_or("something".to_string(), |npc| format!("the {}", npc.name)),
+        Combatant::Player => "you".to_string(),
+    };
+
+    let text = match event {
+        WorldEvent::KeyPickedUp(_) => "You picked up a key.".to_string(),
+        WorldEvent::DoorOpened(_) => "The door opens.".to_string(),
+        WorldEvent::DoorClosed(_) => "A door slams shut.".to_string(),
+        WorldEvent::DoorLocked(_) => "The door is locked.".to_string(),
+        WorldEvent::SwitchFlipped { .. } => "You hear a click.".to_string(),
+        WorldEvent::TreasureCollected { items, .. } if items.is_empty() => {
+            "The chest is empty.".to_string()
+        }
+        WorldEvent::TreasureCollected { items, .. } => {
+            format!("You found {}.", item_list(items, &world.registries.items))
+        }
+        WorldEvent::MapEntered { .. } => "You enter a new area.".to_string(),
+        WorldEvent::Damaged { attacker: Combatant::Player, defender, damage } => {
+            format!("You hit {} for {} damage.", npc_name(defender), damage)
+        }
+        WorldEvent::Damaged { attacker, defender: Combatant::Player, damage } => {
+            capitalize(format!("{} hits you for {} damage.", npc_name(attacker), damage))
+        }
+        WorldEvent::Damaged { .. } => return None,
+        WorldEvent::NpcKilled { name, xp, .. } => {
+            format!("You defeated the {} (+{} XP).", name, xp)
+        }
+        WorldEvent::PlayerDied => "You have died.".to_string(),
+    };
+
+    Some(text)
+}
+
+/// Lists items as `500 gold, Health Potion and 2 x Iron Sword`
+fn item_list(items: &[(TreasureID, TreasureQuantity)], registry: &ItemRegistry) -> String {
+    let names: Vec<String> = items.iter()
+        .map(|&(id, qty)| match (id, qty) {
+            (GOLD_ID, qty) => format!("{} gold", qty),
+            (id, 1) => registry.name(id).to_string(),
+            (id, qty) => format!("{} x {}", qty, registry.name(id)),
+        })
+        .collect();
+
+    match names.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// Upper-cases the first letter of a sentence
+fn capitalize(text: String) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_messages_collapse() {
+        let mut log = MessageLog::default();
+        log.push("The door is locked.");
+        log.push("The door is locked.");
+        log.push("You hear a click.");
+        log.push("The door is locked.");
+
+        let shown: Vec<String> = log.iter().map(Message::display).collect();
+        assert_eq!(
+            shown,
+            ["The door is locked. (x2)", "You hear a click.", "The door is locked."]
+        );
+    }
+
+    #[test]
+    fn oldest_messages_are_dropped_at_capacity() {
+        let mut log = MessageLog::default();
+        for i in 0..MESSAGE_LOG_CAPACITY + 3 {
+            log.push(format!("Message {}", i));
+        }
+
+        assert_eq!(log.len(), MESSAGE_LOG_CAPACITY);
+        assert_eq!(log.iter().next().unwrap().text, "Message 3");
+    }
+}
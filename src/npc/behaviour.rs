@@ -0,0 +1,83 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::shared::constants::FOV_RADIUS;
+use crate::shared::{MoveDirection, Rng, WorldCoordinates};
+use crate::world::{FieldOfView, PathOptions, WorldMap, find_path};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` of the behaviours which decide how an [Npc](crate::npc::Npc) moves each turn
+///
+/// * `Idle` - Never moves
+/// * `Wander` - Steps in a random passable direction, sometimes standing still
+/// * `Patrol` - Walks between the waypoints of its `route` in order, looping back to the first
+/// * `Chase` - Walks towards the player while it can see them, otherwise stands still
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Behaviour {
+    #[default]
+    Idle,
+    Wander,
+    Patrol { route: Vec<WorldCoordinates>, next: usize },
+    Chase,
+}
+
+impl Behaviour {
+    /// Decides the next move of an NPC
+    ///
+    /// # Arguments
+    /// * `coords` ( `WorldCoordinates` ) - Where the NPC is standing
+    /// * `player` ( `WorldCoordinates` ) - Where the player is standing
+    /// * `map` ( `&WorldMap` ) - The map the NPC is on
+    /// * `rng` ( `&mut Rng` ) - The NPC's random number generator
+    ///
+    /// # Returns
+    /// The direction to move in, or `None` to stand still
+    pub fn next_move(
+        &mut self,
+        coords: WorldCoordinates,
+        player: WorldCoordinates,
+        map: &WorldMap,
+        rng: &mut Rng,
+    ) -> Option<MoveDirection> {
+        match self {
+            Behaviour::Idle => None,
+
+            Behaviour::Wander => {
+                // One roll in five stands still
+                let roll = rng.below(MoveDirection::ALL.len() as u64 + 1) as usize;
+                let direction = *MoveDirection::ALL.get(roll)?;
+                direction.step_from(coords)
+                    .is_some_and(|target| map.is_passable(target))
+                    .then_some(direction)
+            }
+
+            Behaviour::Patrol { route, next } => {
+                if route.is_empty() {
+                    return None;
+                }
+                if route[*next % route.len()] == coords {
+                    *next = (*next + 1) % route.len();
+                }
+                step_toward(coords, route[*next % route.len()], map)
+            }
+
+            Behaviour::Chase => {
+                FieldOfView::compute(map, coords, FOV_RADIUS)
+                    .is_visible(player)
+                    .then(|| step_toward(coords, player, map))
+                    .flatten()
+            }
+        }
+    }
+}
+
+/// Takes the first step of the shortest path from `from` to `to`, treating locked doors as walls
+fn step_toward(
+    from: WorldCoordinates,
+    to: WorldCoordinates,
+    map: &WorldMap,
+) -> Option<MoveDirection> {
+    find_path(map, from, to, &PathOptions::default())?.first().copied()
+}
@@ -0,0 +1,18 @@
+//! # *mod* NPC
+//!
+//! Non-player characters and the behaviours which drive them. Every [Npc] belongs to a
+//! [WorldMap](crate::world::WorldMap) (placed with a `~N` directive) and takes one turn after
+//! each turn of the player (see [npc_turns](crate::world::npc_turns)). Behaviours only decide a
+//! [MoveDirection](crate::shared::MoveDirection); the move itself goes through
+//! [translate](crate::world::translate) and follows the same collision rules as the player.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// >> CRATE (RE-EXPORT)
+mod behaviour;
+pub use behaviour::*;
+
+mod npc_entity;
+pub use npc_entity::*;
@@ -0,0 +1,121 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::shared::extlib::NAVector3;
+use crate::shared::traits::{Moveable, Positionable, ToIdentifiableChar};
+use crate::shared::{Column, MoveDirection, Rng, Row, Stats, WorldCoordinates};
+use crate::world::WorldMap;
+
+// > SUPER
+use super::Behaviour;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Encapsulates a non-player character in the game world.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Npc {
+    pub name: String,
+    draw: char,
+    position: NAVector3<i64>,
+    new_position: NAVector3<i64>,
+
+    pub behaviour: Behaviour,
+    pub stats: Stats,
+    rng: Rng,
+}
+
+impl Npc {
+    /// Creates a new `Npc` standing on the given coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the NPC.
+    /// * `draw` - The `char` used to represent the NPC visually.
+    /// * `coords` - The `WorldCoordinates` the NPC starts on.
+    /// * `behaviour` - The `Behaviour` which decides the NPC's moves.
+    /// * `stats` - The NPC's combat `Stats`.
+    /// * `seed` - The seed of the NPC's random moves.
+    pub fn new(
+        name: &str,
+        draw: char,
+        coords: WorldCoordinates,
+        behaviour: Behaviour,
+        stats: Stats,
+        seed: u64,
+    ) -> Self {
+        let position = NAVector3::new(coords.1 as i64, coords.0 as i64, 1);
+        Self {
+            name: name.to_string(),
+            draw,
+            position,
+            new_position: position,
+            behaviour,
+            stats,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the NPC attacks the player when it bumps into them (only chasing NPCs do)
+    pub fn is_hostile(&self) -> bool {
+        matches!(self.behaviour, Behaviour::Chase)
+    }
+
+    /// ## Returns
+    ///
+    /// The experience the player gains for defeating the NPC
+    pub fn xp_reward(&self) -> u32 {
+        self.stats.max_hp + self.stats.attack * 2 + self.stats.defense * 2
+    }
+
+    /// Decides the NPC's next move using its `Behaviour`
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - The map the NPC is on.
+    /// * `player` - The coordinates of the player.
+    pub fn next_move(&mut self, map: &WorldMap, player: WorldCoordinates) -> Option<MoveDirection> {
+        let coords = (self.row(), self.col());
+        self.behaviour.next_move(coords, player, map, &mut self.rng)
+    }
+}
+
+// IMPL Positionable
+impl Positionable for Npc {
+    fn row(&self) -> Row {
+        self.position.y as Row
+    }
+
+    fn col(&self) -> Column {
+        self.position.x as Column
+    }
+}
+
+// IMPL Moveable
+impl Moveable for Npc {
+    fn new_col(&self) -> Column {
+        self.new_position.x as Column
+    }
+
+    fn new_row(&self) -> Row {
+        self.new_position.y as Row
+    }
+
+    fn translate(&mut self, direction: MoveDirection) -> &Self {
+        self.new_position = direction.translate() * self.position;
+        self
+    }
+
+    fn translate_into(&mut self) {
+        self.position = self.new_position;
+    }
+}
+
+// IMPL ToIdentifiableChar
+impl ToIdentifiableChar for Npc {
+    fn to_char_id(&self) -> char {
+        self.draw
+    }
+}
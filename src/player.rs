@@ -1,11 +1,15 @@
 // > USE STD
 use std::collections::HashSet;
 
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > USE CRATE
+use crate::registry::{ItemEffect, ItemRegistry};
+use crate::shared::constants::PLAYER_BASE_STATS;
 use crate::shared::extlib::NAVector3;
 use crate::shared::treasure::*;
 use crate::shared::traits::{
-    Identifiable,
     Moveable, 
     Positionable, 
     ToIdentifiableChar
@@ -14,13 +18,22 @@ use crate::shared::{
     Column, Row,
     KeyDoorLink, 
     MoveDirection,
+    Stats,
+    WorldCoordinates,
 };
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The items a player currently has equipped. Equipped items are no longer in the inventory.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Equipment {
+    pub armor: Option<TreasureID>,
+    pub weapon: Option<TreasureID>,
+}
+
 /// Encapsulates a player in the game world.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     draw: char,
     position: NAVector3<i64>,
@@ -29,6 +42,8 @@ pub struct Player {
     
     pub keyring: Vec<KeyDoorLink>,
     pub inventory: TreasureCollection,
+    pub equipment: Equipment,
+    pub stats: Stats,
 }
 
 impl Player {
@@ -41,7 +56,7 @@ impl Player {
     pub fn new(draw: char, position: NAVector3<i64>) -> Self {
         // Build the player's initial inventory
         let inventory = TreasureCollectionBuilder::new(TreasureCollectionVariant::PlayerInventory)
-            .items(&[(TreasureType::Gold.to_id(), 0)]).build();
+            .items(&[(GOLD_ID, 0)]).build();
 
         // When creating a new player, the current, new-, and previous- position are all the same
         Self {
@@ -51,9 +66,24 @@ impl Player {
             draw,
             position,
             inventory,
+            equipment: Equipment::default(),
+            stats: Stats::new(PLAYER_BASE_STATS.0, PLAYER_BASE_STATS.1, PLAYER_BASE_STATS.2),
         }
     }
 
+    /// Places the player directly on the given coordinates (e.g. when entering another map),
+    /// resetting the current, new-, and previous- position
+    ///
+    /// # Arguments
+    ///
+    /// * `coords` - The `WorldCoordinates` to place the player on.
+    pub fn set_position(&mut self, coords: WorldCoordinates) {
+        let position = NAVector3::new(coords.1 as i64, coords.0 as i64, 1);
+        self.position = position;
+        self.new_position = position;
+        self.previous_position = position;
+    }
+
     /// Fetches the player's gold quantity (`u64`) in a safe way, considering the player's gold
     /// should always sit at index 0 in their `inventory`
     pub fn gold_qty(&self) -> u64 {
@@ -65,6 +95,147 @@ impl Player {
     pub fn add_gold(&mut self, quantity: u64) {
         self.inventory.items[0].1 += quantity;
     }
+
+    /// Fetches the total quantity of the given item in the player's `inventory`
+    pub fn item_qty(&self, id: TreasureID) -> TreasureQuantity {
+        self.inventory.items.iter()
+            .filter(|(t, _)| *t == id)
+            .map(|(_, qty)| *qty)
+            .sum()
+    }
+
+    /// Adds a quantity of the given item to the player's `inventory`, filling existing entries of
+    /// the same item up to its stack limit before starting new ones
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the item to add.
+    /// * `quantity` - The quantity to add.
+    /// * `items` - The `ItemRegistry` defining the item's stack limit.
+    pub fn add_item(
+        &mut self,
+        id: TreasureID,
+        mut quantity: TreasureQuantity,
+        items: &ItemRegistry,
+    ) {
+        let limit = items.stack_limit(id);
+
+        for (_, qty) in self.inventory.items.iter_mut().filter(|(t, _)| *t == id) {
+            let added = quantity.min(limit.saturating_sub(*qty));
+            *qty += added;
+            quantity -= added;
+        }
+
+        while quantity > 0 {
+            let added = quantity.min(limit);
+            self.inventory.items.push((id, added));
+            quantity -= added;
+        }
+    }
+
+    /// Removes one of the given item from the player's `inventory`, dropping the entry once it
+    /// is empty (gold always keeps its entry at index 0)
+    ///
+    /// # Returns
+    ///
+    /// `true` if the player had the item
+    pub fn take_item(&mut self, id: TreasureID) -> bool {
+        let Some(idx) = self.inventory.items.iter().rposition(|(t, q)| *t == id && *q > 0)
+        else {
+            return false;
+        };
+
+        self.inventory.items[idx].1 -= 1;
+        if self.inventory.items[idx].1 == 0 && idx != 0 {
+            self.inventory.items.remove(idx);
+        }
+        true
+    }
+
+    /// Drinks the first potion in the player's `inventory`, applying its `Heal` effects
+    ///
+    /// # Returns
+    ///
+    /// The id of the potion drunk, or `None` if the player had no potion
+    pub fn use_potion(&mut self, items: &ItemRegistry) -> Option<TreasureID> {
+        let id = self.inventory.items.iter()
+            .find(|(t, q)| *q > 0 && items.is_category(*t, TreasureType::Potion))
+            .map(|(t, _)| *t)?;
+        self.take_item(id);
+
+        for effect in items.get(id).map_or(&[][..], |def| &def.effects) {
+            if let ItemEffect::Heal(amount) = effect {
+                self.stats.heal(*amount);
+            }
+        }
+        Some(id)
+    }
+
+    /// Returns the player's total attack: the base attack plus the `Attack` effects of the
+    /// equipped items
+    pub fn attack(&self, items: &ItemRegistry) -> u32 {
+        let bonus = self.equipment_bonus(items, |effect| match effect {
+            ItemEffect::Attack(n) => n,
+            _ => 0,
+        });
+        self.stats.attack.saturating_add_signed(bonus)
+    }
+
+    /// Returns the player's total defense: the base defense plus the `Defense` effects of the
+    /// equipped items
+    pub fn defense(&self, items: &ItemRegistry) -> u32 {
+        let bonus = self.equipment_bonus(items, |effect| match effect {
+            ItemEffect::Defense(n) => n,
+            _ => 0,
+        });
+        self.stats.defense.saturating_add_signed(bonus)
+    }
+
+    /// Sums one kind of effect over every equipped item
+    fn equipment_bonus(&self, items: &ItemRegistry, value: impl Fn(ItemEffect) -> i32) -> i32 {
+        [self.equipment.armor, self.equipment.weapon].into_iter()
+            .flatten()
+            .filter_map(|id| items.get(id))
+            .flat_map(|def| def.effects.iter().copied())
+            .map(value)
+            .sum()
+    }
+
+    /// Equips the first `Armor` or `Weapon` in the player's `inventory`, returning any item
+    /// already in that slot to the `inventory`
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - Either `TreasureType::Armor` or `TreasureType::Weapon`.
+    /// * `items` - The `ItemRegistry` defining the item categories.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an item was equipped
+    pub fn equip(&mut self, category: TreasureType, items: &ItemRegistry) -> bool {
+        let slot = match category {
+            TreasureType::Armor => self.equipment.armor,
+            TreasureType::Weapon => self.equipment.weapon,
+            _ => return false,
+        };
+        // Never swap an item for another copy of itself
+        let Some(id) = self.inventory.items.iter()
+            .find(|(t, q)| *q > 0 && items.is_category(*t, category) && Some(*t) != slot)
+            .map(|(t, _)| *t)
+        else {
+            return false;
+        };
+
+        self.take_item(id);
+        let slot = match category {
+            TreasureType::Armor => &mut self.equipment.armor,
+            _ => &mut self.equipment.weapon,
+        };
+        if let Some(previous) = slot.replace(id) {
+            self.add_item(previous, 1, items);
+        }
+        true
+    }
 }
 
 // IMPL Positionable
@@ -97,6 +268,10 @@ impl Moveable for Player {
     fn translate_into(&mut self) {
         self.position = self.new_position;
     }
+
+    fn is_player(&self) -> bool {
+        true
+    }
 }
 
 // IMPL ToIdentifiableChar
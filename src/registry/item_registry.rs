@@ -0,0 +1,228 @@
+//! # Item Registry
+//!
+//! The definitions of every item which can be found in the world or carried by the player. Items
+//! are defined in a RON file as a list of [ItemDef]s:
+//!
+//! ```text
+//! [
+//!     (
+//!         id: 2,
+//!         name: "Potion",
+//!         glyph: '!',
+//!         category: Potion,
+//!         stack_limit: 10,
+//!         value: 25,
+//!         effects: [Heal(10)],
+//!     ),
+//! ]
+//! ```
+//!
+//! `stack_limit` and `effects` may be omitted (an unlimited stack and no effects). Item
+//! [GOLD_ID] must always be defined as the `Gold` category.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::registry::RegistryError;
+use crate::shared::treasure::{GOLD_ID, TreasureID, TreasureQuantity, TreasureType};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The built-in item definitions, used when no item definition file is present
+const DEFAULT_ITEMS: &str = include_str!("../../assets/items.ron");
+
+/// An `enum` of the effects an item has when it is used or equipped
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ItemEffect {
+    /// Restores hit points when used
+    Heal(u32),
+    /// Increases attack while equipped
+    Attack(i32),
+    /// Increases defense while equipped
+    Defense(i32),
+}
+
+/// The definition of a single item
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemDef {
+    pub id: TreasureID,
+    pub name: String,
+    pub glyph: char,
+    pub category: TreasureType,
+    #[serde(default = "unlimited_stack")]
+    pub stack_limit: TreasureQuantity,
+    pub value: u64,
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+}
+
+/// The default `stack_limit` of an [ItemDef]
+fn unlimited_stack() -> TreasureQuantity {
+    TreasureQuantity::MAX
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Every [ItemDef] known to the game, keyed by id
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ItemRegistry {
+    items: BTreeMap<TreasureID, ItemDef>,
+}
+
+// IMPL Default
+impl Default for ItemRegistry {
+    /// Returns the built-in item definitions
+    fn default() -> Self {
+        Self::from_ron(DEFAULT_ITEMS).expect("Default item definitions must be valid.")
+    }
+}
+
+impl ItemRegistry {
+    /// Loads the item definitions from the RON file at `path`. A missing file yields the
+    /// built-in definitions.
+    ///
+    /// # Arguments
+    /// * `path` ( `impl AsRef<Path>` ) - The path of the item definition file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        match std::fs::read_to_string(path.as_ref()) {
+            Ok(contents) => Self::from_ron(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(RegistryError::Unreadable {
+                path: path.as_ref().display().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    /// Parses the item definitions from the contents of an item definition file
+    pub fn from_ron(contents: &str) -> Result<Self, RegistryError> {
+        let defs: Vec<ItemDef> = ron::from_str(contents)
+            .map_err(|e| RegistryError::Malformed { reason: e.to_string() })?;
+
+        let mut items = BTreeMap::new();
+        for def in defs {
+            let id = def.id;
+            if items.insert(id, def).is_some() {
+                return Err(RegistryError::DuplicateId { id: id.to_string() });
+            }
+        }
+
+        // The player's gold is tracked by id, so it must always exist
+        if items.get(&GOLD_ID).is_none_or(|def| def.category != TreasureType::Gold) {
+            return Err(RegistryError::MissingDefinition {
+                id: GOLD_ID.to_string(), expected: "a Gold item".to_string()
+            });
+        }
+
+        Ok(Self { items })
+    }
+
+    /// ## Returns
+    ///
+    /// The [ItemDef] with the given id, or `None` if no such item is defined
+    pub fn get(&self, id: TreasureID) -> Option<&ItemDef> {
+        self.items.get(&id)
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if an item with the given id is defined
+    pub fn contains(&self, id: TreasureID) -> bool {
+        self.items.contains_key(&id)
+    }
+
+    /// ## Returns
+    ///
+    /// The display name of the item with the given id (ids which are not defined, e.g. from a
+    /// save made with other definitions, are shown as `Unknown item`)
+    pub fn name(&self, id: TreasureID) -> &str {
+        self.get(id).map_or("Unknown item", |def| &def.name)
+    }
+
+    /// ## Returns
+    ///
+    /// The maximum quantity of the item with the given id which fits in one inventory entry
+    pub fn stack_limit(&self, id: TreasureID) -> TreasureQuantity {
+        self.get(id).map_or(TreasureQuantity::MAX, |def| def.stack_limit.max(1))
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the item with the given id is of the given category
+    pub fn is_category(&self, id: TreasureID, category: TreasureType) -> bool {
+        self.get(id).is_some_and(|def| def.category == category)
+    }
+
+    /// ## Returns
+    ///
+    /// An iterator over every [ItemDef], ordered by id
+    pub fn iter(&self) -> impl Iterator<Item = &ItemDef> {
+        self.items.values()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The one item every registry needs
+    const GOLD: &str = r#"(id: 1, name: "Gold", glyph: '$', category: Gold, value: 1),"#;
+
+    #[test]
+    fn bundled_items_load() {
+        let registry = ItemRegistry::default();
+
+        assert!(registry.is_category(GOLD_ID, TreasureType::Gold));
+        assert_eq!(registry.name(2), "Potion");
+        let potion = registry.get(2).unwrap();
+        assert_eq!(potion.stack_limit, 10);
+        assert_eq!(potion.effects, [ItemEffect::Heal(10)]);
+        assert_eq!(registry.stack_limit(GOLD_ID), TreasureQuantity::MAX);
+        assert_eq!(registry.iter().count(), 6);
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let result = ItemRegistry::from_ron(&format!(
+            r#"[{}(id: 2, name: "Potion", glyph: '!', category: Potion, value: 25),
+                  (id: 2, name: "Elixir", glyph: '!', category: Potion, value: 50)]"#,
+            GOLD
+        ));
+
+        assert_eq!(result, Err(RegistryError::DuplicateId { id: "2".to_string() }));
+    }
+
+    #[test]
+    fn gold_must_be_defined() {
+        let result = ItemRegistry::from_ron(
+            r#"[(id: 1, name: "Potion", glyph: '!', category: Potion, value: 25)]"#
+        );
+
+        assert!(matches!(result, Err(RegistryError::MissingDefinition { .. })));
+
+        let result = ItemRegistry::from_ron("[(id: 1)]");
+        assert!(matches!(result, Err(RegistryError::Malformed { .. })));
+    }
+
+    #[test]
+    fn unknown_ids_have_fallbacks() {
+        let registry = ItemRegistry::from_ron(&format!("[{}]", GOLD)).unwrap();
+
+        assert_eq!(registry.get(99), None);
+        assert!(!registry.contains(99));
+        assert_eq!(registry.name(99), "Unknown item");
+        assert_eq!(registry.stack_limit(99), TreasureQuantity::MAX);
+        assert!(!registry.is_category(99, TreasureType::Gold));
+    }
+}
@@ -0,0 +1,56 @@
+//! # *mod* Registry
+//!
+//! Data-driven definitions loaded from asset files at startup. Game content (the items found in
+//! treasure chests and the tiles maps are built from) is described in RON files so it can be
+//! extended without recompiling; the rest of the engine refers to definitions by their registry
+//! id.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::path::Path;
+
+// >> CRATE (RE-EXPORT)
+mod item_registry;
+pub use item_registry::*;
+
+mod registry_error;
+pub use registry_error::*;
+
+mod tile_registry;
+pub use tile_registry::*;
+
+/// The default path of the item definition file
+pub const ITEMS_PATH: &str = "assets/items.ron";
+
+/// The default path of the tile definition file
+pub const TILES_PATH: &str = "assets/tiles.ron";
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Every registry the game is built from
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Registries {
+    pub items: ItemRegistry,
+    pub tiles: TileRegistry,
+}
+
+impl Registries {
+    /// Loads every registry from its definition file, using the built-in definitions for any
+    /// file which is missing
+    ///
+    /// # Arguments
+    /// * `items_path` ( `impl AsRef<Path>` ) - The path of the item definition file
+    /// * `tiles_path` ( `impl AsRef<Path>` ) - The path of the tile definition file
+    pub fn load(
+        items_path: impl AsRef<Path>,
+        tiles_path: impl AsRef<Path>,
+    ) -> Result<Self, RegistryError> {
+        Ok(Self {
+            items: ItemRegistry::load(items_path)?,
+            tiles: TileRegistry::load(tiles_path)?,
+        })
+    }
+}
@@ -0,0 +1,47 @@
+//! # Registry Error
+//!
+//! Errors produced while loading a definition registry from its asset file.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::fmt;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing the ways loading a registry can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// The definition file exists but could not be read
+    Unreadable { path: String, reason: String },
+    /// The definition file is not a valid RON list of definitions
+    Malformed { reason: String },
+    /// Two definitions share the same id (an item id or a tile glyph)
+    DuplicateId { id: String },
+    /// A definition the engine relies on is missing or has the wrong shape
+    MissingDefinition { id: String, expected: String },
+    /// A definition has a combination of fields which is not allowed
+    InvalidDefinition { id: String, reason: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable { path, reason } => {
+                write!(f, "unable to read definition file {}: {}", path, reason)
+            }
+            Self::Malformed { reason } => write!(f, "invalid definition file: {}", reason),
+            Self::DuplicateId { id } => write!(f, "definition id {} is defined more than once", id),
+            Self::MissingDefinition { id, expected } => {
+                write!(f, "definition {} must be defined as {}", id, expected)
+            }
+            Self::InvalidDefinition { id, reason } => {
+                write!(f, "invalid definition {}: {}", id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
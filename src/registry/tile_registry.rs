@@ -0,0 +1,370 @@
+//! # Tile Registry
+//!
+//! The definitions of every tile a map may use, keyed by the glyph which places the tile in a map
+//! file. Tiles are defined in a RON file as a list of [TileDef]s:
+//!
+//! ```text
+//! #![enable(implicit_some)]
+//! [
+//!     (
+//!         glyph: '|',
+//!         name: "Locked Door",
+//!         opaque: true,
+//!         fg: "yellow",
+//!         interaction: Door,
+//!         state: Locked,
+//!     ),
+//! ]
+//! ```
+//!
+//! Every field other than `glyph` and `name` may be omitted. Door tiles must have a `Locked` or
+//! `Unlocked` state, treasure tiles an `Uncollected` or `Collected` state and lever and pressure
+//! plate tiles an `Active` or `Inactive` state; an `Unlocked` door and a `Collected` treasure must
+//! always be defined, as opened doors and looted chests turn into them. Tiles whose state changes
+//! during play take on the look of the first definition with their interaction and new state, if
+//! there is one.
+//!
+//! The map formats reserve a few characters, which can never be tile glyphs: the `~` directive
+//! marker, the space which lets lower layers of a map document show through, and control
+//! characters such as line breaks.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// > USE 3P
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::registry::RegistryError;
+use crate::shared::{CommonState, Tile, TileProperties};
+use crate::world::DIRECTIVE_MARKER;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The built-in tile definitions, used when no tile definition file is present
+const DEFAULT_TILES: &str = include_str!("../../assets/tiles.ron");
+
+/// The characters the map formats reserve for themselves (control characters are reserved too)
+const RESERVED_GLYPHS: [char; 2] = [DIRECTIVE_MARKER, ' '];
+
+/// An `enum` of the ways the world reacts to a tile, which decides the [Tile] variant created
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TileInteraction {
+    /// Plain terrain; only its `passable` flag matters
+    #[default]
+    None,
+    /// A door, opened by the key linked to it with a `~K` directive
+    Door,
+    /// A key, picked up by walking onto it
+    Key,
+    /// A treasure chest, looted with a `~T` directive's contents
+    Treasure,
+    /// A lever, toggled by walking into it, which toggles the tiles linked to it with a `~W`
+    /// directive
+    Lever,
+    /// A pressure plate, active while anything stands on it, which toggles the tiles linked to it
+    /// with a `~W` directive when it is stepped on and again when it is stepped off
+    Plate,
+}
+
+impl TileInteraction {
+    /// ## Returns
+    ///
+    /// The interaction which created the `tile`
+    pub fn of(tile: &Tile) -> Self {
+        match tile {
+            Tile::Terrain(_) => TileInteraction::None,
+            Tile::Door(_, _) => TileInteraction::Door,
+            Tile::Key(_) => TileInteraction::Key,
+            Tile::Treasure(_, _) => TileInteraction::Treasure,
+            Tile::Lever(_, _) => TileInteraction::Lever,
+            Tile::Plate(_, _) => TileInteraction::Plate,
+        }
+    }
+}
+
+/// An `enum` of the initial states a tile can be defined with (see [CommonState])
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TileState {
+    Locked,
+    Unlocked,
+    Uncollected,
+    Collected,
+    Active,
+    Inactive,
+}
+
+impl TileState {
+    /// Returns the [CommonState] the tile is created in
+    pub fn common_state(self) -> CommonState {
+        match self {
+            TileState::Locked => CommonState::LOCKED,
+            TileState::Unlocked => CommonState::UNLOCKED,
+            TileState::Uncollected => CommonState::UNCOLLECTED,
+            TileState::Collected => CommonState::COLLECTED,
+            TileState::Active => CommonState::ACTIVE,
+            TileState::Inactive => CommonState::INACTIVE,
+        }
+    }
+}
+
+/// The definition of a single tile
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TileDef {
+    pub glyph: char,
+    pub name: String,
+    #[serde(default)]
+    pub passable: bool,
+    #[serde(default)]
+    pub opaque: bool,
+    /// The glyph drawn for the tile, if different from the glyph used in map files
+    #[serde(default)]
+    pub display: Option<char>,
+    #[serde(default)]
+    pub fg: Option<Color>,
+    #[serde(default)]
+    pub bg: Option<Color>,
+    #[serde(default)]
+    pub state: Option<TileState>,
+    #[serde(default)]
+    pub interaction: TileInteraction,
+}
+
+impl TileDef {
+    /// Applies the look and physical properties of this definition to a tile's properties,
+    /// leaving its position and links untouched
+    pub fn apply_to(&self, props: &mut TileProperties) {
+        props.draw_character = self.display.unwrap_or(self.glyph);
+        props.passable = self.passable;
+        props.opaque = self.opaque;
+        props.fg = self.fg;
+        props.bg = self.bg;
+    }
+
+    /// Checks that the `glyph` is not reserved by the map formats and that the `state` suits the
+    /// `interaction`
+    fn validate(&self) -> Result<(), RegistryError> {
+        if RESERVED_GLYPHS.contains(&self.glyph) || self.glyph.is_control() {
+            return Err(RegistryError::InvalidDefinition {
+                id: format!("{:?}", self.glyph),
+                reason: "the glyph is reserved by the map format".to_string(),
+            });
+        }
+
+        let valid = match self.interaction {
+            TileInteraction::None | TileInteraction::Key => self.state.is_none(),
+            TileInteraction::Door => {
+                matches!(self.state, Some(TileState::Locked | TileState::Unlocked))
+            }
+            TileInteraction::Treasure => {
+                matches!(self.state, Some(TileState::Uncollected | TileState::Collected))
+            }
+            TileInteraction::Lever | TileInteraction::Plate => {
+                matches!(self.state, Some(TileState::Active | TileState::Inactive))
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(RegistryError::InvalidDefinition {
+                id: self.glyph.to_string(),
+                reason: format!(
+                    "{:?} tiles cannot have the state {:?}", self.interaction, self.state
+                ),
+            })
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Every [TileDef] known to the game, keyed by glyph
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TileRegistry {
+    tiles: BTreeMap<char, TileDef>,
+}
+
+// IMPL Default
+impl Default for TileRegistry {
+    /// Returns the built-in tile definitions
+    fn default() -> Self {
+        Self::from_ron(DEFAULT_TILES).expect("Default tile definitions must be valid.")
+    }
+}
+
+impl TileRegistry {
+    /// Loads the tile definitions from the RON file at `path`. A missing file yields the
+    /// built-in definitions.
+    ///
+    /// # Arguments
+    /// * `path` ( `impl AsRef<Path>` ) - The path of the tile definition file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        match std::fs::read_to_string(path.as_ref()) {
+            Ok(contents) => Self::from_ron(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(RegistryError::Unreadable {
+                path: path.as_ref().display().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    /// Parses the tile definitions from the contents of a tile definition file
+    pub fn from_ron(contents: &str) -> Result<Self, RegistryError> {
+        let defs: Vec<TileDef> = ron::from_str(contents)
+            .map_err(|e| RegistryError::Malformed { reason: e.to_string() })?;
+
+        let mut tiles = BTreeMap::new();
+        for def in defs {
+            def.validate()?;
+            let glyph = def.glyph;
+            if tiles.insert(glyph, def).is_some() {
+                return Err(RegistryError::DuplicateId { id: glyph.to_string() });
+            }
+        }
+
+        let registry = Self { tiles };
+
+        // Opening a door and looting a chest look up the tiles they turn into
+        let required = [
+            (TileInteraction::Door, CommonState::UNLOCKED, "an Unlocked Door tile"),
+            (TileInteraction::Treasure, CommonState::COLLECTED, "a Collected Treasure tile"),
+        ];
+        for (interaction, state, expected) in required {
+            if registry.for_state(interaction, state).is_none() {
+                return Err(RegistryError::MissingDefinition {
+                    id: format!("{:?}", interaction), expected: expected.to_string()
+                });
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Creates the [Tile] placed by the given glyph in a map file
+    ///
+    /// # Returns
+    ///
+    /// `Some(Tile)` for a defined glyph, or `None` if the glyph is not a known tile
+    pub fn create(&self, glyph: char) -> Option<Tile> {
+        let def = self.get(glyph)?;
+
+        let mut props = TileProperties::default();
+        def.apply_to(&mut props);
+
+        // Reveal keys in debug mode
+        if def.interaction == TileInteraction::Key && cfg!(debug_assertions) {
+            props.draw_character = def.glyph;
+        }
+
+        // Door, treasure, lever and plate definitions are validated to have a state
+        let state = def.state.map(TileState::common_state);
+        let tile = match def.interaction {
+            TileInteraction::None => Tile::Terrain(props),
+            TileInteraction::Door => Tile::Door(props, state?),
+            TileInteraction::Key => Tile::Key(props),
+            TileInteraction::Treasure => Tile::Treasure(props, state?),
+            TileInteraction::Lever => Tile::Lever(props, state?),
+            TileInteraction::Plate => Tile::Plate(props, state?),
+        };
+
+        Some(tile)
+    }
+
+    /// ## Returns
+    ///
+    /// The [TileDef] placed by the given glyph, or `None` if no such tile is defined
+    pub fn get(&self, glyph: char) -> Option<&TileDef> {
+        self.tiles.get(&glyph)
+    }
+
+    /// ## Returns
+    ///
+    /// The first [TileDef] (by glyph) with the given interaction and initial state, used to
+    /// restyle tiles whose state changes during play
+    pub fn for_state(&self, interaction: TileInteraction, state: CommonState) -> Option<&TileDef> {
+        self.tiles.values().find(|def| {
+            def.interaction == interaction && def.state.map(TileState::common_state) == Some(state)
+        })
+    }
+
+    /// ## Returns
+    ///
+    /// The first [TileDef] (by glyph) with the given interaction, used to restyle stateless
+    /// tiles such as keys once they've been used
+    pub fn for_interaction(&self, interaction: TileInteraction) -> Option<&TileDef> {
+        self.tiles.values().find(|def| def.interaction == interaction)
+    }
+
+    /// ## Returns
+    ///
+    /// An iterator over every [TileDef], ordered by glyph
+    pub fn iter(&self) -> impl Iterator<Item = &TileDef> {
+        self.tiles.values()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The fewest tiles a registry needs: an open door and a looted chest
+    const REQUIRED_TILES: &str = r#"
+        (glyph: '\\', name: "Open Door", passable: true, interaction: Door, state: Unlocked),
+        (glyph: '0', name: "Looted Chest", interaction: Treasure, state: Collected),
+    "#;
+
+    /// Parses the required tiles followed by the `extra` definitions
+    fn parse(extra: &str) -> Result<TileRegistry, RegistryError> {
+        TileRegistry::from_ron(&format!("#![enable(implicit_some)]\n[{}{}]", REQUIRED_TILES, extra))
+    }
+
+    #[test]
+    fn bundled_tiles_load() {
+        let registry = TileRegistry::default();
+
+        assert_eq!(registry.get('#').map(|def| def.opaque), Some(true));
+        assert!(matches!(registry.create('|'), Some(Tile::Door(_, CommonState::LOCKED))));
+        assert!(matches!(registry.create('D'), Some(Tile::Treasure(_, CommonState::UNCOLLECTED))));
+        assert!(registry.iter().all(|def| def.validate().is_ok()));
+        assert_eq!(registry.create('?'), None);
+    }
+
+    #[test]
+    fn duplicate_glyphs_are_rejected() {
+        let result = parse(r#"(glyph: '.', name: "Floor"), (glyph: '.', name: "Sand"),"#);
+
+        assert_eq!(result, Err(RegistryError::DuplicateId { id: ".".to_string() }));
+    }
+
+    #[test]
+    fn reserved_glyphs_are_rejected() {
+        for glyph in ["~", " ", "\\n"] {
+            let result = parse(&format!(r#"(glyph: '{}', name: "Water"),"#, glyph));
+
+            assert!(
+                matches!(result, Err(RegistryError::InvalidDefinition { ref reason, .. })
+                    if reason.contains("reserved")),
+                "{:?}: {:?}", glyph, result
+            );
+        }
+    }
+
+    #[test]
+    fn states_must_suit_the_interaction() {
+        let result = parse(r#"(glyph: '|', name: "Door", interaction: Door, state: Active),"#);
+        assert!(matches!(result, Err(RegistryError::InvalidDefinition { .. })));
+
+        let result = TileRegistry::from_ron(r#"[(glyph: '.', name: "Floor")]"#);
+        assert!(matches!(result, Err(RegistryError::MissingDefinition { .. })));
+    }
+}
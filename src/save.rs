@@ -0,0 +1,214 @@
+//! # Save
+//!
+//! Persists the full game state to disk and restores it. A save file is a versioned RON
+//! document containing the [Player], every loaded [WorldMap] (including mutated tile states such
+//! as opened doors, collected chests and picked-up keys, and the NPCs on it), the active map, the
+//! world clock and any pending or scheduled [WorldUpdate]s.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::path::Path;
+
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::Player;
+use crate::world::{WorldController, WorldMap, WorldUpdate, WorldUpdateEventType};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The current version of the save file format. Bump this once for each release in which a
+/// persisted type changes shape, so saves of older releases are rejected instead of being misread.
+pub(crate) const SAVE_FORMAT_VERSION: u32 = 1;
+
+/// The default path used for quick saving and loading
+pub(crate) const QUICKSAVE_PATH: &str = "saves/quicksave.ron";
+
+/// The on-disk representation of a saved game
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    player: Player,
+    active_map_id: usize,
+    maps: Vec<WorldMap>,
+    ticks: u64,
+    next_update_sequence: u64,
+    pending_updates: Vec<WorldUpdate<WorldUpdateEventType>>,
+}
+
+/// Only the version of a save file, read before the rest to reject unsupported formats
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing the ways saving or loading a game can fail
+#[derive(Debug)]
+pub enum SaveError {
+    /// The save file could not be read or written
+    Io(std::io::Error),
+    /// The game state could not be serialized
+    Serialize(ron::Error),
+    /// The save file is not a valid save document
+    Deserialize(ron::error::SpannedError),
+    /// The save file was written by an incompatible version of the format
+    UnsupportedVersion { found: u32, expected: u32 },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "unable to access save file: {}", e),
+            Self::Serialize(e) => write!(f, "unable to serialize game state: {}", e),
+            Self::Deserialize(e) => write!(f, "invalid save file: {}", e),
+            Self::UnsupportedVersion { found, expected } => write!(
+                f, "unsupported save file version {} (expected {})", found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Saves the full game state to the file at `path`, creating any missing parent directories
+///
+/// # Arguments
+/// * `path` (`impl AsRef<Path>`) - The path of the save file
+/// * `player` (`&Player`) - An ***immutable*** reference to the player
+/// * `world` (`&WorldController`) - An ***immutable*** reference to the world controller
+pub fn save_game(
+    path: impl AsRef<Path>,
+    player: &Player,
+    world: &WorldController,
+) -> Result<(), SaveError> {
+    let contents = serialize_game(player, world)?;
+
+    if let Some(parent) = path.as_ref().parent() {
+        std::fs::create_dir_all(parent).map_err(SaveError::Io)?;
+    }
+    std::fs::write(path, contents).map_err(SaveError::Io)
+}
+
+/// Loads the full game state from the file at `path`, replacing the maps, active map and
+/// pending updates of the `world`. The world is left untouched if loading fails.
+///
+/// # Arguments
+/// * `path` (`impl AsRef<Path>`) - The path of the save file
+/// * `world` (`&mut WorldController`) - A ***mutable reference*** to the world controller
+///
+/// # Returns
+/// The restored [Player]
+pub fn load_game(
+    path: impl AsRef<Path>,
+    world: &mut WorldController,
+) -> Result<Player, SaveError> {
+    let contents = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+    deserialize_game(&contents, world)
+}
+
+/// Serializes the full game state into the save file format
+pub(crate) fn serialize_game(
+    player: &Player,
+    world: &WorldController,
+) -> Result<String, SaveError> {
+    let save = SaveFile {
+        version: SAVE_FORMAT_VERSION,
+        player: player.clone(),
+        active_map_id: world.active_map_id,
+        maps: world.maps.clone(),
+        ticks: world.ticks,
+        next_update_sequence: world.next_update_sequence,
+        // Keep the heap's internal order so a reloaded queue serializes identically
+        pending_updates: world.update_queue.as_slice().to_vec(),
+    };
+
+    ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default())
+        .map_err(SaveError::Serialize)
+}
+
+/// Deserializes the save file format into the `world`, returning the restored [Player]
+pub(crate) fn deserialize_game(
+    contents: &str,
+    world: &mut WorldController,
+) -> Result<Player, SaveError> {
+    let header: SaveHeader = ron::from_str(contents).map_err(SaveError::Deserialize)?;
+    if header.version != SAVE_FORMAT_VERSION {
+        return Err(SaveError::UnsupportedVersion {
+            found: header.version,
+            expected: SAVE_FORMAT_VERSION,
+        });
+    }
+
+    let save: SaveFile = ron::from_str(contents).map_err(SaveError::Deserialize)?;
+
+    world.maps = save.maps;
+    world.active_map_id = save.active_map_id;
+    world.ticks = save.ticks;
+    world.next_update_sequence = save.next_update_sequence;
+    world.update_queue = BinaryHeap::from(save.pending_updates);
+
+    Ok(save.player)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::extlib::NAVector3;
+    use crate::shared::{CommonState, MoveDirection};
+    use crate::world;
+
+    #[test]
+    fn round_trip_preserves_mid_game_state() {
+        let mut world = WorldController::new();
+        world.load_map_from_fstr("assets/test_map1.txt").unwrap();
+        world.load_map_from_fstr("assets/test_map2.txt").unwrap();
+
+        // Walk next to the chest at (21,1), loot it, then pick up the key at (18,1)
+        let mut player = Player::new('@', NAVector3::new(1, 22, 1));
+        world::pickup_explicit(&mut player, &mut world, world::ExplicitPickupType::TreasureChest);
+        world.update_world(&mut player);
+        player.set_position((17, 1));
+        world::translate(&mut player, &mut world, MoveDirection::DOWN);
+        world.update_world(&mut player);
+
+        // Leave an update pending
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::TryOpenDoor((16, 36))));
+
+        let saved = serialize_game(&player, &world).unwrap();
+
+        let mut reloaded = WorldController::new();
+        let reloaded_player = deserialize_game(&saved, &mut reloaded).unwrap();
+
+        assert_eq!(reloaded_player, player);
+        assert_eq!(reloaded_player.gold_qty(), 500);
+        assert_eq!(reloaded_player.keyring.len(), 1);
+        assert_eq!(reloaded.maps, world.maps);
+        assert_eq!(reloaded.update_queue.len(), 1);
+        assert_eq!(reloaded.maps[0].grid[(21, 1)].get_state(), Some(&CommonState::COLLECTED));
+        assert_eq!(serialize_game(&reloaded_player, &reloaded).unwrap(), saved);
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut world = WorldController::new();
+
+        let result = deserialize_game("(version: 0)", &mut world);
+
+        assert!(matches!(
+            result,
+            Err(SaveError::UnsupportedVersion { found: 0, expected: SAVE_FORMAT_VERSION })
+        ));
+    }
+}
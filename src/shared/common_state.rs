@@ -1,3 +1,6 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > SUPER
 use super::traits::Identifiable;
 
@@ -8,15 +11,24 @@ use super::traits::Identifiable;
 /// #### Version: 0.0.1
 ///
 /// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
 pub struct CommonState(u8);
 
 // IMPL
 impl CommonState {
     pub const UNLOCKED: Self = Self(1);
     pub const COLLECTED: Self = Self(1);
+    pub const ACTIVE: Self = Self(1);
     pub const LOCKED: Self = Self(2);
     pub const UNCOLLECTED: Self = Self(2);
+    pub const INACTIVE: Self = Self(2);
+
+    /// ## Returns
+    ///
+    /// The opposite state ( e.g. `LOCKED` for `UNLOCKED`, `INACTIVE` for `ACTIVE` )
+    pub fn toggled(self) -> Self {
+        if self.0 == 1 { Self(2) } else { Self(1) }
+    }
 }
 
 impl Identifiable for CommonState {
@@ -1,6 +1,50 @@
+//
+// > USE
+//
+use std::time::Duration;
+
 //
 // > CONST
 //
 
-/// The ***char*** width of tiles as a `u16` 
+/// The number of screen cells ( columns ) a single tile occupies as a `u16`.
+///
+/// Only the view layer converts between tiles and screen cells; every `Grid<Tile>` and
+/// `WorldCoordinates` is in logical tile units.
 pub(crate) const TILE_WIDTH: u16 = 2;
+
+/// The number of screen cells ( rows ) a single tile occupies as a `u16`
+pub(crate) const TILE_HEIGHT: u16 = 1;
+
+/// The starting ( max_hp, attack, defense ) of the player
+pub(crate) const PLAYER_BASE_STATS: (u32, u32, u32) = (20, 3, 1);
+
+/// The ( max_hp, attack, defense ) of an NPC whose `~N` directive does not define its stats
+pub(crate) const NPC_BASE_STATS: (u32, u32, u32) = (6, 2, 0);
+
+/// How far the player can see, in tiles
+pub(crate) const FOV_RADIUS: usize = 8;
+
+/// How much game time a single simulation tick advances. The game loop runs the simulation at
+/// this fixed rate no matter how often it renders.
+pub const TICK_DURATION: Duration = Duration::from_millis(50);
+
+/// The minimum time between two rendered frames
+pub const FRAME_DURATION: Duration = Duration::from_millis(33);
+
+/// The most ticks the game loop runs to catch up after falling behind ( e.g. while the process
+/// was suspended ). Any further backlog is dropped rather than simulated.
+pub const MAX_CATCH_UP_TICKS: u32 = 20;
+
+/// How many ticks pass between two NPC turns in [TimeMode::RealTime](crate::world::TimeMode)
+pub(crate) const NPC_TURN_TICKS: u64 = 10;
+
+/// The most messages the message log keeps before dropping the oldest
+pub(crate) const MESSAGE_LOG_CAPACITY: usize = 500;
+
+/// The default half-extent ( rows, cols ) of the camera dead-zone, in tiles
+pub(crate) const CAMERA_DEAD_ZONE: (usize, usize) = (2, 4);
+
+/// The default minimum distance ( rows, cols ) kept between the player and the view edges, in
+/// tiles
+pub(crate) const CAMERA_MARGIN: (usize, usize) = (3, 6);
@@ -1,20 +1,34 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > SUPER / CRATE
-use crate::world::WorldMap;
 use super::{MoveDirection, WorldCoordinates};
 
 /// ## DoorMapLink
 ///
 /// A struct containing the [WorldCoordinates](crate::shared::WorldCoordinates) of a door tile which
 /// enables transitioning to another map `usize` id once the player moves onto the tile and then 
-/// moves off of the tile in the defined MoveDirection
+/// moves off of the tile in the defined MoveDirection. The player is placed on the `entry` point
+/// of the target map.
 ///
 /// #### Version: 0.0.1
 ///
 /// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
 /// 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DoorMapLink {
     pub door_coords: WorldCoordinates,
     pub transition_direction: MoveDirection,
-    pub map_id: usize
-}
\ No newline at end of file
+    pub map_id: usize,
+    pub entry: MapEntry,
+}
+
+/// Where the player is placed on the target map of a [DoorMapLink]
+///
+/// * `Coords` - A fixed tile
+/// * `Spawn` - A named spawn point of the target map, declared with a `~S` directive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapEntry {
+    Coords(WorldCoordinates),
+    Spawn(String),
+}
@@ -1,3 +1,6 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > SUPER
 use super::WorldCoordinates;
 
@@ -10,7 +13,7 @@ use super::WorldCoordinates;
 /// #### Version: 0.0.1
 ///
 /// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct KeyDoorLink {
     pub key_coords: WorldCoordinates,
     pub door_coords: WorldCoordinates,
@@ -8,34 +8,34 @@
 //! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
 
 // >> 3P (RE-EXPORT)
-pub(crate) mod extlib {
-    pub(crate) use crossterm::event::{
+pub mod extlib {
+    pub use crossterm::event::{
         self as crossterm_event, Event as CrosstermEvent, KeyCode as CrosstermKeyCode,
     };
-    pub(crate) use nalgebra::{Matrix3 as NAMatrix3, Vector3 as NAVector3};
-    pub(crate) use ratatui::{
-        DefaultTerminal as RatatuiDefaultTerminal,
+    pub use nalgebra::{Matrix3 as NAMatrix3, Vector3 as NAVector3};
+    pub use ratatui::{
+        DefaultTerminal as RatatuiDefaultTerminal, Frame as RatatuiFrame,
         buffer::Buffer as RatatuiBuffer,
         layout::Rect as RatatuiRect,
-        widgets::Widget as RatatuiWidget,
+        widgets::{StatefulWidget as RatatuiStatefulWidget, Widget as RatatuiWidget},
     };
 }
 
 // >> CRATE (RE-EXPORT)
-pub(crate) mod constants;
-pub(crate) mod traits;
-pub(crate) mod treasure;
+pub mod constants;
+pub mod traits;
+pub mod treasure;
 
 // >> PRIMITIVE TYPE ALIASES
 
 /// The row (y) coordinate within the grid as `usize`
-pub(crate) type Row = usize;
+pub type Row = usize;
 
 /// The column (x) coordinate within the grid as `usize`
-pub(crate) type Column = usize;
+pub type Column = usize;
 
 /// A tuple container for the Row, Column ( y,x ) coordinates within the grid as `(usize, usize)`
-pub(crate) type WorldCoordinates = (Row, Column);
+pub type WorldCoordinates = (Row, Column);
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -43,6 +43,7 @@ mod common_state;
 pub use common_state::CommonState;
 
 mod door_map_link;
+pub use door_map_link::{DoorMapLink, MapEntry};
 
 mod key_door_link;
 pub use key_door_link::KeyDoorLink;
@@ -50,5 +51,11 @@ pub use key_door_link::KeyDoorLink;
 mod move_direction;
 pub use move_direction::MoveDirection;
 
+mod rng;
+pub use rng::Rng;
+
+mod stats;
+pub use stats::{hit_damage, Stats};
+
 mod tile;
-pub use tile::Tile;
+pub use tile::{Tile, TileProperties};
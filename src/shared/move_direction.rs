@@ -1,5 +1,9 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > SUPER
 use super::extlib::NAMatrix3;
+use super::WorldCoordinates;
 
 /// The row (y) direction within the grid as `i64`
 type MoveRow = i64;
@@ -17,7 +21,7 @@ type MoveSpeed = Option<i64>;
 /// #### Version: 0.0.1
 ///
 /// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MoveDirection(MoveRow, MoveCol, MoveSpeed);
 
 impl MoveDirection {
@@ -26,6 +30,40 @@ impl MoveDirection {
     pub const LEFT: Self = Self(0, -1, Some(1));
     pub const RIGHT: Self = Self(0, 1, Some(1));
 
+    /// Every direction, in the order `UP`, `DOWN`, `LEFT`, `RIGHT`
+    pub const ALL: [Self; 4] = [Self::UP, Self::DOWN, Self::LEFT, Self::RIGHT];
+
+    /// Parses a `MoveDirection` from its constant name (`UP`, `DOWN`, `LEFT` or `RIGHT`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "UP" => Some(Self::UP),
+            "DOWN" => Some(Self::DOWN),
+            "LEFT" => Some(Self::LEFT),
+            "RIGHT" => Some(Self::RIGHT),
+            _ => None,
+        }
+    }
+
+    /// Returns the constant name of the `MoveDirection` (the inverse of
+    /// [MoveDirection::from_name]), or `None` if it isn't one of the four constants
+    pub fn name(&self) -> Option<&'static str> {
+        match *self {
+            Self::UP => Some("UP"),
+            Self::DOWN => Some("DOWN"),
+            Self::LEFT => Some("LEFT"),
+            Self::RIGHT => Some("RIGHT"),
+            _ => None,
+        }
+    }
+
+    /// Returns the coordinates one step from `coords` in this direction, or `None` if the step
+    /// would leave the grid on its top or left edge
+    pub fn step_from(&self, coords: WorldCoordinates) -> Option<WorldCoordinates> {
+        let row = coords.0.checked_add_signed(self.0 as isize)?;
+        let col = coords.1.checked_add_signed(self.1 as isize)?;
+        Some((row, col))
+    }
+
     pub fn translate(&self) -> NAMatrix3<i64> {
         let ty: i64;
         let tx: i64;
@@ -0,0 +1,38 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+/// ## Rng
+///
+/// A small deterministic random number generator (SplitMix64). Its state is saved with whatever
+/// owns it, so the same seed always replays the same sequence, including across save and load.
+///
+/// #### Version: 0.0.1
+///
+/// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new `Rng` from a seed (any value, including 0, is a valid seed)
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next pseudo-random `u64`
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudo-random value in `0..bound`
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `bound` is 0
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
@@ -0,0 +1,125 @@
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+/// The experience needed per level to reach the next level (level 1 needs 20 XP, level 2 needs
+/// 40 XP, ...)
+const XP_PER_LEVEL: u32 = 20;
+
+/// ## Stats
+///
+/// The combat statistics shared by the player and NPCs. `attack` and `defense` are the base
+/// values; equipment bonuses are added on top by the owner.
+///
+/// #### Version: 0.0.1
+///
+/// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub hp: u32,
+    pub max_hp: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub level: u32,
+    pub xp: u32,
+}
+
+impl Stats {
+    /// Creates new level 1 `Stats` at full health
+    ///
+    /// # Arguments
+    /// * `max_hp` ( `u32` ) - The maximum (and starting) hit points
+    /// * `attack` ( `u32` ) - The base attack
+    /// * `defense` ( `u32` ) - The base defense
+    pub fn new(max_hp: u32, attack: u32, defense: u32) -> Self {
+        Self { hp: max_hp, max_hp, attack, defense, level: 1, xp: 0 }
+    }
+
+    /// ## Returns
+    ///
+    /// `true` once the hit points have run out
+    pub fn is_dead(&self) -> bool {
+        self.hp == 0
+    }
+
+    /// Removes up to `amount` hit points
+    pub fn take_damage(&mut self, amount: u32) {
+        self.hp = self.hp.saturating_sub(amount);
+    }
+
+    /// Restores up to `amount` hit points, never above `max_hp`
+    pub fn heal(&mut self, amount: u32) {
+        self.hp = self.hp.saturating_add(amount).min(self.max_hp);
+    }
+
+    /// ## Returns
+    ///
+    /// The experience needed to reach the next level
+    pub fn xp_to_next_level(&self) -> u32 {
+        self.level * XP_PER_LEVEL
+    }
+
+    /// Adds experience, levelling up as many times as it allows. Each level raises `max_hp` by
+    /// 5 and `attack` and `defense` by 1, and restores full health.
+    ///
+    /// # Returns
+    /// The number of levels gained
+    pub fn gain_xp(&mut self, amount: u32) -> u32 {
+        self.xp += amount;
+
+        let mut levels = 0;
+        while self.xp >= self.xp_to_next_level() {
+            self.xp -= self.xp_to_next_level();
+            self.level += 1;
+            self.max_hp += 5;
+            self.attack += 1;
+            self.defense += 1;
+            self.hp = self.max_hp;
+            levels += 1;
+        }
+        levels
+    }
+}
+
+/// Calculates the damage of one hit: the attacker's attack minus the defender's defense, but
+/// always at least 1
+///
+/// # Arguments
+/// * `attack` ( `u32` ) - The attacker's total attack
+/// * `defense` ( `u32` ) - The defender's total defense
+pub fn hit_damage(attack: u32, defense: u32) -> u32 {
+    attack.saturating_sub(defense).max(1)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaining_xp_levels_up_once_per_threshold() {
+        let mut stats = Stats::new(20, 3, 1);
+        stats.take_damage(15);
+
+        assert_eq!(stats.gain_xp(19), 0);
+        assert_eq!((stats.level, stats.xp, stats.hp), (1, 19, 5));
+
+        // 20 XP for level 2, then 40 XP for level 3, with 9 left over
+        assert_eq!(stats.gain_xp(50), 2);
+        assert_eq!((stats.level, stats.xp), (3, 9));
+        assert_eq!(stats.xp_to_next_level(), 60);
+        assert_eq!(stats, Stats { hp: 30, max_hp: 30, attack: 5, defense: 3, level: 3, xp: 9 });
+    }
+
+    #[test]
+    fn damage_and_healing_stay_within_bounds() {
+        let mut stats = Stats::new(10, 2, 2);
+
+        stats.heal(5);
+        assert_eq!(stats.hp, 10);
+        stats.take_damage(25);
+        assert!(stats.is_dead());
+        assert_eq!(hit_damage(2, 5), 1);
+        assert_eq!(hit_damage(7, 2), 5);
+    }
+}
@@ -1,9 +1,14 @@
+// > USE 3P
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
 // > USE CRATE
 use crate::shared::treasure::*;
-use crate::shared::traits::{IdentifiableChar, Positionable};
+use crate::shared::traits::Positionable;
 use crate::shared::{
     Column, 
     CommonState, 
+    DoorMapLink,
     KeyDoorLink, 
     Row, 
     WorldCoordinates
@@ -17,26 +22,29 @@ use super::traits::ToIdentifiableChar;
 ///
 /// A struct compartmentalizing the various types of `Tile` which can pass a
 /// [TileProperties](crate::shared::TileProperties) and possibly a 
-/// [CommonState](crate::shared::CommonState)
+/// [CommonState](crate::shared::CommonState). Tiles are created from the definitions in the
+/// [TileRegistry](crate::registry::TileRegistry); every tile without an interaction is `Terrain`,
+/// whose passability comes from its properties.
 ///
 /// #### Version: 0.0.1
 ///
 /// #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Tile {
     Door(TileProperties, CommonState),
-    Floor(TileProperties),
     Key(TileProperties),
+    Terrain(TileProperties),
     Treasure(TileProperties, CommonState),
-    Wall(TileProperties),
+    Lever(TileProperties, CommonState),
+    Plate(TileProperties, CommonState),
 }
 
 // IMPL Default
 impl Default for Tile {
 
-    /// Returns a default tile (Floor tile)
+    /// Returns a default tile (impassable Terrain tile)
     fn default() -> Tile {
-        Tile::Floor(TileProperties::default())
+        Tile::Terrain(TileProperties::default())
     }
 }
 
@@ -49,10 +57,11 @@ impl Tile {
     pub fn get_properties_mut(&mut self) -> &mut TileProperties {
         match self {
             Tile::Door(props, _) => props,
-            Tile::Floor(props) => props,
             Tile::Key(props) => props,
-            Tile::Wall(props) => props,
+            Tile::Terrain(props) => props,
             Tile::Treasure(props, _) => props,
+            Tile::Lever(props, _) => props,
+            Tile::Plate(props, _) => props,
         }
     }
 
@@ -62,10 +71,11 @@ impl Tile {
     pub fn get_properties(&self) -> &TileProperties {
         match self {
             Tile::Door(props, _) => props,
-            Tile::Floor(props) => props,
             Tile::Key(props) => props,
-            Tile::Wall(props) => props,
+            Tile::Terrain(props) => props,
             Tile::Treasure(props, _) => props,
+            Tile::Lever(props, _) => props,
+            Tile::Plate(props, _) => props,
         }
     }
 
@@ -75,25 +85,49 @@ impl Tile {
     /// * `new_state` ( `CommonState` ) - A [CommonState](crate::shared::CommonState)
     pub fn change_state(&mut self, new_state: CommonState) {
         match self {
-            Tile::Door(_, state) => *state = new_state,
-            Tile::Treasure(_, state) => *state = new_state,
+            Tile::Door(_, state)
+            | Tile::Treasure(_, state)
+            | Tile::Lever(_, state)
+            | Tile::Plate(_, state) => *state = new_state,
             _ => {}
         }
     }
 
     /// ## Returns
     /// 
-    /// * `Some(&CommonState)` - If the `Tile` is a `Door` or `Treasure`, returns a reference 
-    ///   to its `CommonState`.
-    /// * `None` - If the `Tile` is a `Floor`, `Key`, or `Wall`, returns `None`.
+    /// * `Some(&CommonState)` - If the `Tile` is a `Door`, `Treasure`, `Lever` or `Plate`,
+    ///   returns a reference to its `CommonState`.
+    /// * `None` - If the `Tile` is a `Key` or `Terrain`, returns `None`.
     pub fn get_state(&self) -> Option<&CommonState> {
         match self {
-            Tile::Door(_, state) => Some(state),
-            Tile::Treasure(_, state) => Some(state),
+            Tile::Door(_, state)
+            | Tile::Treasure(_, state)
+            | Tile::Lever(_, state)
+            | Tile::Plate(_, state) => Some(state),
             // Return None for variants without state
-            Tile::Floor(_) | Tile::Key(_) | Tile::Wall(_) => None,
+            Tile::Key(_) | Tile::Terrain(_) => None,
         }
     }
+
+    /// ## Returns
+    ///
+    /// `true` if a `Moveable` can stand on the `Tile` without interacting with it first (an
+    /// unlocked door, or a passable key, terrain or pressure plate tile)
+    pub fn is_passable(&self) -> bool {
+        match self {
+            Tile::Door(_, state) => *state == CommonState::UNLOCKED,
+            Tile::Key(props) | Tile::Terrain(props) | Tile::Plate(props, _) => props.passable,
+            Tile::Treasure(_, _) | Tile::Lever(_, _) => false,
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the `Tile` is a lever or pressure plate, which changes the state of the tiles
+    /// linked to it with a `~W` directive
+    pub fn is_switch(&self) -> bool {
+        matches!(self, Tile::Lever(_, _) | Tile::Plate(_, _))
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -113,70 +147,6 @@ impl Positionable for Tile {
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-impl IdentifiableChar for Tile {
-
-    /// Creates a `Tile` from a given character identifier.
-    ///
-    /// # Arguments
-    ///
-    /// * `id` - A character representing the type of the tile.
-    ///
-    /// # Returns
-    ///
-    /// A `Tile` instance corresponding to the provided character identifier.
-    ///
-    /// # Panics
-    ///
-    /// Panics if an invalid character identifier is supplied.
-    fn from_char_id(id: char) -> Self {
-        match id {
-            '|' => Self::Door(
-                TileProperties {
-                    draw_character: '|',
-                    ..Default::default()
-                },
-                CommonState::LOCKED,
-            ),
-            '\\' => Self::Door(
-                TileProperties {
-                    draw_character: '\\',
-                    ..Default::default()
-                },
-                CommonState::UNLOCKED,
-            ),
-            '.' => Self::Floor(TileProperties {
-                draw_character: '.',
-                ..Default::default()
-            }),
-            'K' => Self::Key(TileProperties {
-                draw_character: {
-                    if cfg!(debug_assertions) { 'K' } else { '.' } // Reveal keys in debug mode
-                },
-                ..Default::default()
-            }),
-            'D' => Self::Treasure(
-                TileProperties {
-                    draw_character: 'D',
-                    ..Default::default()
-                },
-                CommonState::UNCOLLECTED,
-            ),
-            '0' => Self::Treasure(
-                TileProperties {
-                    draw_character: '0',
-                    ..Default::default()
-                },
-                CommonState::COLLECTED,
-            ),
-            '#' => Self::Wall(TileProperties {
-                draw_character: '#',
-                ..Default::default()
-            }),
-            _ => panic!("Invalid identity char supplied for Tile"),
-        }
-    }
-}
-
 impl ToIdentifiableChar for Tile {
 
     /// Returns the character identifier for the `Tile`.
@@ -191,14 +161,21 @@ impl ToIdentifiableChar for Tile {
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TileProperties {
     pub row: Row,
     pub col: Column,
     pub world_coordinates: WorldCoordinates,
     pub draw_character: char,
+    pub passable: bool,
+    pub opaque: bool,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
     pub treasure: Option<TreasureCollection>,
-    pub kdl: Option<KeyDoorLink>
+    pub kdl: Option<KeyDoorLink>,
+    pub dml: Option<DoorMapLink>,
+    /// The tiles a lever or pressure plate toggles
+    pub switch_targets: Vec<WorldCoordinates>,
 }
 
 // IMPL TileProperties
@@ -225,6 +202,26 @@ impl TileProperties {
     pub fn link_door(&mut self, kdl: KeyDoorLink) {
         self.kdl = Some(kdl);
     }
+
+    /// Links a door to another map through a door-map link.
+    ///
+    /// ## Arguments
+    ///
+    /// * `dml` - The door-map link to be associated with the door
+    /// 
+    pub fn link_map(&mut self, dml: DoorMapLink) {
+        self.dml = Some(dml);
+    }
+
+    /// Links a lever or pressure plate to the tiles it toggles.
+    ///
+    /// ## Arguments
+    ///
+    /// * `targets` - The coordinates of the tiles toggled by the switch
+    /// 
+    pub fn link_switch(&mut self, targets: Vec<WorldCoordinates>) {
+        self.switch_targets = targets;
+    }
 }
 
 // IMPL Default
@@ -236,8 +233,14 @@ impl Default for TileProperties {
             col: 0,
             world_coordinates: (0, 0),
             draw_character: '?',
+            passable: false,
+            opaque: false,
+            fg: None,
+            bg: None,
             treasure: None,
-            kdl: None
+            kdl: None,
+            dml: None,
+            switch_targets: vec![],
         }
     }
 }
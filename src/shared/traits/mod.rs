@@ -1,8 +1,8 @@
 mod identifiable;
-pub(crate) use identifiable::*;
+pub use identifiable::*;
 
 mod positionable;
-pub(crate) use positionable::*;
+pub use positionable::*;
 
 mod moveable;
-pub(crate) use moveable::*;
+pub use moveable::*;
@@ -13,4 +13,10 @@ pub trait Moveable: Positionable {
     fn new_col(&self) -> Column;
     fn translate(&mut self, direction: MoveDirection) -> &Self;
     fn translate_into(&mut self);
+
+    /// Whether this is the player. Only the player picks up keys, opens doors and moves between
+    /// maps; every other `Moveable` is simply blocked by them.
+    fn is_player(&self) -> bool {
+        false
+    }
 }
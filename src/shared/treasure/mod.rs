@@ -4,6 +4,9 @@ pub type TreasureID = usize;
 /// The quantity of the treasure as `u64`
 pub type TreasureQuantity = u64;
 
+/// The id of the gold item, which every [ItemRegistry](crate::registry::ItemRegistry) defines
+pub const GOLD_ID: TreasureID = 1;
+
 mod treasure_collection;
 pub use treasure_collection::*;
 
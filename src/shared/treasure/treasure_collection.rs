@@ -1,20 +1,24 @@
 //
 //
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > CRATE / SUPER
-use crate::shared::{WorldCoordinates, traits::{Identifiable, IdentifiableFrom}};
-use super::{TreasureID, TreasureQuantity, TreasureType};
+use crate::shared::WorldCoordinates;
+use super::{TreasureID, TreasureQuantity};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// ### TreasureCollection
 /// 
 /// Encapsulates a TreasureCollection (constructed using the
-/// [TreasureCollectionBuilder](crate::shared::TreasureCollectionBuilder))
+/// [TreasureCollectionBuilder](crate::shared::TreasureCollectionBuilder)). Items refer to
+/// [ItemRegistry](crate::registry::ItemRegistry) ids.
 /// 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TreasureCollection {
     pub variant: TreasureCollectionVariant,
-    pub items: Vec<(TreasureType, TreasureQuantity)>,
+    pub items: Vec<(TreasureID, TreasureQuantity)>,
     pub world_coords: Option<WorldCoordinates>,
 }
 
@@ -34,8 +38,8 @@ impl TreasureCollection {
 /// * `Uninst` - An uninstantiated TreasureCollection (used for starting the `builder`) process
 /// * `TreasureChest` - A TreasureCollection used on a `Tile` type
 /// * `PlayerInventory` - A TreasureCollection used within the `Player` type 
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
-pub(crate) enum TreasureCollectionVariant {
+#[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TreasureCollectionVariant {
     #[default]
     Uninst,
     TreasureChest,
@@ -51,7 +55,7 @@ pub(crate) enum TreasureCollectionVariant {
 #[derive(Default)]
 pub struct TreasureCollectionBuilder {
     variant: TreasureCollectionVariant,
-    items: Vec<(TreasureType, TreasureQuantity)>,
+    items: Vec<(TreasureID, TreasureQuantity)>,
     world_coords: Option<WorldCoordinates>,
 }
 
@@ -91,12 +95,7 @@ impl TreasureCollectionBuilder {
         match self.variant {
             TreasureCollectionVariant::PlayerInventory
             | TreasureCollectionVariant::TreasureChest => {
-                items.iter().for_each(|(tid, tqty)| {
-                    self.items.push((
-                        TreasureType::from_id(*tid),
-                        *tqty
-                    ))
-                });
+                self.items.extend_from_slice(items);
                 self
             }
             _ => panic!(
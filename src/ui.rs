@@ -0,0 +1,232 @@
+//! # UI
+//!
+//! Builds the screen layout (map, inventory, stats and messages panels) and renders the game state
+//! into a ratatui `Frame`. Rendering only reads game state; the only state owned here is the
+//! [UiState].
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE 3P
+use ratatui::{
+    layout::{Layout, Direction, Constraint},
+    text::{Line, Text},
+    widgets::{Block, Borders, Padding, Paragraph}
+};
+
+// > CRATE
+use crate::message_log::MessageLog;
+use crate::shared::extlib::RatatuiFrame;
+use crate::registry::ItemRegistry;
+use crate::shared::treasure::GOLD_ID;
+use crate::world::{Camera, WorldController, WorldView};
+use crate::Player;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The presentation state of the UI which persists between frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiState {
+    pub show_stats: bool,
+    pub show_inventory: bool,
+    pub show_messages: bool,
+    /// How many messages the messages panel is scrolled back from the newest one
+    pub message_scroll: usize,
+    pub camera: Camera,
+}
+
+// IMPL Default
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            show_stats: true,
+            show_inventory: true,
+            show_messages: true,
+            message_scroll: 0,
+            camera: Camera::default(),
+        }
+    }
+}
+
+/// Renders the world view and the visible panels into the `frame`
+///
+/// # Arguments
+/// * `frame` ( `&mut Frame` ) - The frame being drawn
+/// * `player` ( `&Player` ) - An ***immutable*** reference to the player
+/// * `world` ( `&WorldController` ) - An ***immutable*** reference to the world controller
+/// * `log` ( `&MessageLog` ) - The messages shown in the messages panel
+/// * `ui` ( `&mut UiState` ) - A ***mutable*** reference to the UI state
+pub fn draw(
+    frame: &mut RatatuiFrame,
+    player: &Player,
+    world: &WorldController,
+    log: &MessageLog,
+    ui: &mut UiState,
+) {
+    let vertical_chunks = if ui.show_stats || ui.show_messages {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(30),
+                Constraint::Percentage(30),
+            ])
+            .split(frame.area())
+    } else {
+        // Full screen
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+            ])
+            .split(frame.area())
+    };
+
+    let horizontal_chunks = if ui.show_inventory {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(70),
+                Constraint::Percentage(30),
+            ])
+            .split(vertical_chunks[0])
+    } else {
+        // Full screen
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+            ])
+            .split(vertical_chunks[0])
+    };
+
+    // Create the WorldView which will handle rendering the map
+    let world_view = WorldView::new(world, player);
+
+    // Render the WorldView in the game area
+    frame.render_stateful_widget(world_view, horizontal_chunks[0], &mut ui.camera);
+
+    // Render inventory if visible
+    if ui.show_inventory {
+        let inventory_block = Block::default()
+            .title("Inventory")
+            .borders(Borders::ALL)
+            .padding(Padding::new(1, 1, 1, 1));
+
+        let inventory_text = Paragraph::new(inventory_lines(player, &world.registries.items))
+            .block(inventory_block);
+
+        frame.render_widget(inventory_text, horizontal_chunks[1]);
+    }
+
+    if !ui.show_stats && !ui.show_messages {
+        return;
+    }
+
+    // Stats and messages share the bottom row, each taking all of it when shown alone
+    let bottom_chunks = if ui.show_stats && ui.show_messages {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(30),
+                Constraint::Percentage(70),
+            ])
+            .split(vertical_chunks[1])
+    } else {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+            ])
+            .split(vertical_chunks[1])
+    };
+
+    // Render stats if visible
+    if ui.show_stats {
+        let stats_block = Block::default()
+            .title("Stats")
+            .borders(Borders::ALL)
+            .padding(Padding::new(1, 1, 1, 1));
+
+        let stats_text = Paragraph::new(stats_lines(player, &world.registries.items))
+            .block(stats_block);
+
+        frame.render_widget(stats_text, bottom_chunks[0]);
+    }
+
+    // Render messages if visible
+    if ui.show_messages {
+        let area = *bottom_chunks.last().expect("the bottom row has at least one chunk");
+        let messages_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1));
+
+        let height = messages_block.inner(area).height as usize;
+        // Never scroll past the oldest message
+        ui.message_scroll = ui.message_scroll.min(log.len().saturating_sub(height));
+
+        let title = match ui.message_scroll {
+            0 => "Messages".to_string(),
+            scroll => format!("Messages (-{})", scroll),
+        };
+        let messages_text = Paragraph::new(message_lines(log, height, ui.message_scroll))
+            .block(messages_block.title(title));
+
+        frame.render_widget(messages_text, area);
+    }
+}
+
+/// Builds the lines of the messages panel: the `height` messages ending `scroll` messages before
+/// the newest one, oldest first
+fn message_lines(log: &MessageLog, height: usize, scroll: usize) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = log.iter()
+        .rev()
+        .skip(scroll)
+        .take(height)
+        .map(|message| Line::raw(message.display()))
+        .collect();
+    lines.reverse();
+    lines
+}
+
+/// Builds the lines of the stats panel
+fn stats_lines(player: &Player, items: &ItemRegistry) -> Vec<Line<'static>> {
+    let stats = &player.stats;
+    let mut lines = vec![
+        Line::raw(format!(
+            "Level: {} ({}/{} XP)", stats.level, stats.xp, stats.xp_to_next_level()
+        )),
+        Line::raw(format!("HP: {}/{}", stats.hp, stats.max_hp)),
+        Line::raw(format!(
+            "ATK: {}  DEF: {}", player.attack(items), player.defense(items)
+        )),
+        Line::raw(format!("Gold: {}", player.gold_qty())),
+    ];
+
+    if stats.is_dead() {
+        lines.insert(0, Line::raw("You have died."));
+    }
+    lines
+}
+
+/// Builds the lines of the inventory panel: every carried item (gold is shown in the stats
+/// panel) followed by the equipped items
+fn inventory_lines(player: &Player, items: &ItemRegistry) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = player.inventory.items.iter()
+        .filter(|(id, _)| *id != GOLD_ID)
+        .map(|(id, qty)| {
+            let glyph = items.get(*id).map_or('?', |def| def.glyph);
+            Line::raw(format!("{} {} x{}", glyph, items.name(*id), qty))
+        })
+        .collect();
+
+    let equipped = [player.equipment.weapon, player.equipment.armor];
+    for id in equipped.into_iter().flatten() {
+        lines.push(Line::raw(format!("{} (equipped)", items.name(id))));
+    }
+
+    if lines.is_empty() {
+        lines.push(Line::raw("Empty"));
+    }
+    lines
+}
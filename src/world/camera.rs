@@ -0,0 +1,167 @@
+//! # Camera
+//!
+//! Determines which window of the active map's `Grid<Tile>` is visible when the map is larger than
+//! the area it is rendered into. The camera follows a target (the player) using a dead-zone and
+//! margin, and is clamped so it never scrolls past the map edges.
+//!
+//! All values are in ***tile*** coordinates, not screen cells.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > CRATE
+use crate::shared::constants::{CAMERA_DEAD_ZONE, CAMERA_MARGIN};
+use crate::shared::{Column, Row, WorldCoordinates};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A `(rows, columns)` pair of tile extents
+pub type TileExtent = (Row, Column);
+
+/// Tracks the top-left tile of the visible window of the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Camera {
+    /// The half-extent ( rows, cols ) of the box around the view center in which the target can
+    /// move without the camera scrolling
+    pub dead_zone: TileExtent,
+    /// The minimum number of tiles ( rows, cols ) kept between the target and the view edges
+    pub margin: TileExtent,
+    offset: WorldCoordinates,
+}
+
+impl Camera {
+    /// Creates a new `Camera` positioned at the top-left of the map
+    ///
+    /// # Arguments
+    /// * `dead_zone` ( `TileExtent` ) - The half-extent of the dead-zone around the view center
+    /// * `margin` ( `TileExtent` ) - The minimum distance kept between the target and view edges
+    pub fn new(dead_zone: TileExtent, margin: TileExtent) -> Self {
+        Self { dead_zone, margin, offset: (0, 0) }
+    }
+
+    /// ## Returns
+    ///
+    /// The ( row, col ) of the top-left visible tile
+    pub fn offset(&self) -> WorldCoordinates {
+        self.offset
+    }
+
+    /// Scrolls the camera so the `target` stays within the dead-zone (and at least `margin` tiles
+    /// from the view edges), then clamps the camera to the map edges
+    ///
+    /// # Arguments
+    /// * `target` ( `WorldCoordinates` ) - The tile the camera follows
+    /// * `view` ( `TileExtent` ) - The number of tiles that fit in the render area
+    /// * `map` ( `TileExtent` ) - The size of the map in tiles
+    pub fn follow(&mut self, target: WorldCoordinates, view: TileExtent, map: TileExtent) {
+        self.offset = (
+            follow_axis(self.offset.0, target.0, view.0, map.0, self.dead_zone.0, self.margin.0),
+            follow_axis(self.offset.1, target.1, view.1, map.1, self.dead_zone.1, self.margin.1),
+        );
+    }
+}
+
+// IMPL Default
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(CAMERA_DEAD_ZONE, CAMERA_MARGIN)
+    }
+}
+
+/// Computes the new camera offset along a single axis
+fn follow_axis(
+    offset: usize,
+    target: usize,
+    view: usize,
+    map: usize,
+    dead_zone: usize,
+    margin: usize,
+) -> usize {
+    // The whole axis fits, so there is nothing to scroll
+    if map <= view || view == 0 {
+        return 0;
+    }
+
+    // The band ( relative to the view ) the target may occupy without scrolling
+    let center = view / 2;
+    let mut low = center.saturating_sub(dead_zone).max(margin);
+    let mut high = (center + dead_zone).min(view.saturating_sub(margin + 1));
+    if low > high {
+        (low, high) = (center, center);
+    }
+
+    let offset = if target < offset + low {
+        target.saturating_sub(low)
+    } else if target > offset + high {
+        target - high
+    } else {
+        offset
+    };
+
+    offset.min(map - view)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 20x40 view onto a 100x200 map
+    const VIEW: TileExtent = (20, 40);
+    const MAP: TileExtent = (100, 200);
+
+    #[test]
+    fn maps_smaller_than_the_view_never_scroll() {
+        let mut camera = Camera::new((2, 4), (3, 6));
+        camera.follow((15, 30), VIEW, (18, 40));
+
+        assert_eq!(camera.offset(), (0, 0));
+        assert_eq!(follow_axis(7, 15, 20, 18, 2, 3), 0);
+        assert_eq!(follow_axis(7, 15, 0, 18, 2, 3), 0);
+    }
+
+    #[test]
+    fn targets_without_a_dead_zone_stay_centred() {
+        let mut camera = Camera::new((0, 0), (0, 0));
+
+        camera.follow((50, 100), VIEW, MAP);
+        assert_eq!(camera.offset(), (40, 80));
+        camera.follow((51, 99), VIEW, MAP);
+        assert_eq!(camera.offset(), (41, 79));
+    }
+
+    #[test]
+    fn targets_move_freely_inside_the_dead_zone() {
+        let mut camera = Camera::new((2, 4), (3, 6));
+        camera.follow((50, 100), VIEW, MAP);
+        assert_eq!(camera.offset(), (38, 76));
+
+        // The dead zone spans view rows 8 to 12 and view columns 16 to 24
+        camera.follow((46, 92), VIEW, MAP);
+        assert_eq!(camera.offset(), (38, 76));
+        camera.follow((45, 101), VIEW, MAP);
+        assert_eq!(camera.offset(), (37, 77));
+    }
+
+    #[test]
+    fn camera_is_clamped_at_each_edge() {
+        let mut camera = Camera::new((2, 4), (3, 6));
+
+        camera.follow((1, 2), VIEW, MAP);
+        assert_eq!(camera.offset(), (0, 0));
+        camera.follow((99, 199), VIEW, MAP);
+        assert_eq!(camera.offset(), (80, 160));
+        camera.follow((99, 2), VIEW, MAP);
+        assert_eq!(camera.offset(), (80, 0));
+        camera.follow((0, 199), VIEW, MAP);
+        assert_eq!(camera.offset(), (0, 160));
+    }
+
+    #[test]
+    fn oversized_margins_keep_the_target_centred() {
+        // A margin of 12 leaves no room in a 20 tile view, so the target is kept centred
+        assert_eq!(follow_axis(0, 50, 20, 100, 2, 12), 40);
+    }
+}
@@ -0,0 +1,236 @@
+//! # Field of View
+//!
+//! Computes which tiles of a [WorldMap] can be seen from a point using recursive shadowcasting.
+//! The map is scanned in eight octants around the origin, row by row outwards, and every opaque
+//! tile (walls, closed doors, ...) casts a shadow over the tiles behind it.
+//!
+//! Tiles the player has seen before are remembered per map (see [WorldMap::explored]), so every
+//! tile is either [Visibility::Visible], [Visibility::Remembered] or [Visibility::Unseen].
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE 3P
+use grid::Grid;
+
+// > CRATE
+use crate::shared::WorldCoordinates;
+
+// > SUPER
+use super::WorldMap;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The transforms ( xx, xy, yx, yy ) mapping the scan of the first octant onto each of the eight
+/// octants
+const OCTANTS: [(isize, isize, isize, isize); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+/// An `enum` classifying how much the player knows about a tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// The tile is in the player's field of view
+    Visible,
+    /// The tile is out of view, but the player has seen it before
+    Remembered,
+    /// The player has never seen the tile
+    Unseen,
+}
+
+/// The tiles of a [WorldMap] visible from a single point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldOfView {
+    visible: Grid<bool>,
+}
+
+impl FieldOfView {
+    /// Computes the tiles of the `map` visible from `origin` within `radius` tiles
+    ///
+    /// # Arguments
+    /// * `map` ( `&WorldMap` ) - The map to look across
+    /// * `origin` ( `WorldCoordinates` ) - The tile being looked from
+    /// * `radius` ( `usize` ) - How far can be seen, in tiles
+    pub fn compute(map: &WorldMap, origin: WorldCoordinates, radius: usize) -> Self {
+        let mut fov = Self { visible: Grid::new(map.grid.rows(), map.grid.cols()) };
+        if let Some(cell) = fov.visible.get_mut(origin.0, origin.1) {
+            *cell = true;
+        }
+
+        for octant in OCTANTS {
+            fov.cast_light(map, origin, radius, 1, 1.0, 0.0, octant);
+        }
+        fov
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the tile at `coords` is in view
+    pub fn is_visible(&self, coords: WorldCoordinates) -> bool {
+        self.visible.get(coords.0, coords.1).copied().unwrap_or(false)
+    }
+
+    /// ## Returns
+    ///
+    /// The [Visibility] of the tile at `coords`, using the tiles remembered by the `map`
+    pub fn visibility(&self, map: &WorldMap, coords: WorldCoordinates) -> Visibility {
+        if self.is_visible(coords) {
+            Visibility::Visible
+        } else if map.is_explored(coords) {
+            Visibility::Remembered
+        } else {
+            Visibility::Unseen
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// An iterator over the coordinates of every visible tile
+    pub fn iter_visible(&self) -> impl Iterator<Item = WorldCoordinates> + '_ {
+        self.visible.indexed_iter().filter(|(_, seen)| **seen).map(|(coords, _)| coords)
+    }
+
+    /// Scans one octant from `row` outwards, lighting every tile between the `start` and `end`
+    /// slopes and recursing around opaque tiles
+    #[allow(clippy::too_many_arguments)]
+    fn cast_light(
+        &mut self,
+        map: &WorldMap,
+        origin: WorldCoordinates,
+        radius: usize,
+        row: usize,
+        mut start: f64,
+        end: f64,
+        (xx, xy, yx, yy): (isize, isize, isize, isize),
+    ) {
+        if start < end {
+            return;
+        }
+
+        let radius_squared = (radius * radius) as isize;
+        let mut new_start = start;
+
+        for distance in row..=radius {
+            let dy = -(distance as isize);
+            let mut blocked = false;
+
+            for dx in -(distance as isize)..=0 {
+                let left_slope = (dx as f64 - 0.5) / (dy as f64 + 0.5);
+                let right_slope = (dx as f64 + 0.5) / (dy as f64 - 0.5);
+                if start < right_slope {
+                    continue;
+                } else if end > left_slope {
+                    break;
+                }
+
+                let r = origin.0 as isize + dx * yx + dy * yy;
+                let c = origin.1 as isize + dx * xx + dy * xy;
+                let tile = (r >= 0 && c >= 0)
+                    .then(|| map.grid.get(r as usize, c as usize))
+                    .flatten();
+
+                // Tiles outside the map are never lit and block sight
+                let opaque = tile.is_none_or(|t| t.get_properties().opaque);
+                if tile.is_some() && dx * dx + dy * dy < radius_squared {
+                    self.visible[(r as usize, c as usize)] = true;
+                }
+
+                if blocked {
+                    if opaque {
+                        new_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start = new_start;
+                    }
+                } else if opaque && distance < radius {
+                    blocked = true;
+                    self.cast_light(
+                        map, origin, radius, distance + 1, start, left_slope, (xx, xy, yx, yy)
+                    );
+                    new_start = right_slope;
+                }
+            }
+
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registries;
+
+    /// Parses a map with the built-in definitions
+    fn parse(bytes: &[u8]) -> WorldMap {
+        WorldMap::from_bytes(bytes, 0, &Registries::default()).unwrap()
+    }
+
+    #[test]
+    fn walls_hide_the_tiles_behind_them() {
+        let map = parse(b"\
+###########
+#.........#
+#....#....#
+#.........#
+#....#....#
+#.........#
+###########
+");
+
+        // Looking right along row 2, and down along column 5
+        let fov = FieldOfView::compute(&map, (2, 1), 20);
+        assert!(fov.is_visible((2, 4)));
+        assert!(fov.is_visible((2, 5)));
+        assert!(!fov.is_visible((2, 6)));
+        assert!(!fov.is_visible((2, 9)));
+        assert!(fov.is_visible((1, 9)));
+
+        let fov = FieldOfView::compute(&map, (1, 5), 20);
+        assert!(fov.is_visible((2, 5)));
+        assert!(!fov.is_visible((3, 5)));
+        assert!(fov.is_visible((3, 4)));
+
+        // Every octant sees the open room around the origin
+        let fov = FieldOfView::compute(&map, (3, 5), 20);
+        let neighbours = [(2, 4), (2, 6), (3, 4), (3, 6), (4, 4), (4, 6)];
+        assert!(neighbours.iter().all(|&coords| fov.is_visible(coords)));
+        assert!(fov.is_visible((0, 0)) && fov.is_visible((6, 10)));
+    }
+
+    #[test]
+    fn radius_limits_the_view() {
+        let map = parse(b"############\n#..........#\n############\n");
+
+        // Tiles exactly `radius` away are already out of sight
+        let fov = FieldOfView::compute(&map, (1, 1), 3);
+        assert!(fov.is_visible((1, 3)));
+        assert!(!fov.is_visible((1, 4)));
+    }
+
+    #[test]
+    fn explored_tiles_are_remembered() {
+        let mut map = parse(b"############\n#..........#\n############\n");
+
+        let fov = FieldOfView::compute(&map, (1, 1), 3);
+        map.explore(&fov);
+        let fov = FieldOfView::compute(&map, (1, 10), 3);
+        map.explore(&fov);
+
+        assert_eq!(fov.visibility(&map, (1, 9)), Visibility::Visible);
+        assert_eq!(fov.visibility(&map, (1, 2)), Visibility::Remembered);
+        assert_eq!(fov.visibility(&map, (1, 5)), Visibility::Unseen);
+    }
+}
@@ -0,0 +1,639 @@
+//! # Map Directives
+//!
+//! The tokenizer and parser for the directive section of a map file: every line from the first
+//! line starting with `~` to the end of the file. Each directive describes something the grid
+//! alone cannot, such as which door a key opens or what a treasure chest holds.
+//!
+//! ## Grammar
+//!
+//! ```text
+//! line      = [ section | directive ] [ comment ]
+//! comment   = "//" { any character }
+//! section   = "~" "[" NAME "]"
+//! directive = key | treasure | link | npc | spawn | switch
+//!
+//! key       = "~" "K" coords "=" "D" coords
+//! treasure  = "~" "T" coords "=" "(" item { "," item } ")"
+//! link      = "~" "M" coords "=" DIRECTION "->" NUMBER "(" ( NUMBER "," NUMBER | NAME ) ")"
+//! npc       = "~" "N" coords "=" NAME "(" GLYPH ")" BEHAVIOUR { coords }
+//!             [ "STATS" "(" NUMBER "," NUMBER "," NUMBER ")" ]
+//! spawn     = "~" "S" coords "=" NAME
+//! switch    = "~" "W" coords "=" coords { coords }
+//!
+//! coords    = "(" NUMBER "," NUMBER ")"
+//! item      = NUMBER "=" NUMBER
+//! DIRECTION = "UP" | "DOWN" | "LEFT" | "RIGHT"
+//! BEHAVIOUR = "IDLE" | "WANDER" | "CHASE" | "PATROL"
+//! ```
+//!
+//! Whitespace is allowed between any two tokens, and blank lines are ignored. Comments are only
+//! recognised in the directive section, as any character of the grid may be a tile. Sections are
+//! optional and only group directives: a directive placed under the section of another kind
+//! (e.g. a `~N` line under `~[keys]`) is reported as a warning, as are unknown sections,
+//! unknown directive kinds and lines which are not directives at all.
+//!
+//! New directive kinds are added by extending [DirectiveKind] and [MapDirective] and parsing them
+//! in `parse_directive`.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::str::FromStr;
+
+// > CRATE
+use crate::npc::Behaviour;
+use crate::shared::treasure::{TreasureID, TreasureQuantity};
+use crate::shared::{DoorMapLink, KeyDoorLink, MapEntry, MoveDirection, WorldCoordinates};
+
+// > SUPER
+use super::{MapParseErrorKind, MapParseWarning};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The character which starts every directive line. The map grid ends at the first line starting
+/// with it, so it can never be used as a tile glyph
+pub const DIRECTIVE_MARKER: char = '~';
+
+/// An `enum` of every kind of directive, and the letter and section name each one uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    KeyDoor,
+    Treasure,
+    DoorMap,
+    Npc,
+    Spawn,
+    Switch,
+}
+
+impl DirectiveKind {
+    pub const ALL: [DirectiveKind; 6] = [
+        DirectiveKind::KeyDoor,
+        DirectiveKind::Treasure,
+        DirectiveKind::DoorMap,
+        DirectiveKind::Npc,
+        DirectiveKind::Spawn,
+        DirectiveKind::Switch,
+    ];
+
+    /// ## Returns
+    ///
+    /// The letter following the `~` of the directive
+    pub fn letter(&self) -> &'static str {
+        match self {
+            DirectiveKind::KeyDoor => "K",
+            DirectiveKind::Treasure => "T",
+            DirectiveKind::DoorMap => "M",
+            DirectiveKind::Npc => "N",
+            DirectiveKind::Spawn => "S",
+            DirectiveKind::Switch => "W",
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// The name of the section grouping directives of this kind
+    pub fn section(&self) -> &'static str {
+        match self {
+            DirectiveKind::KeyDoor => "keys",
+            DirectiveKind::Treasure => "treasure",
+            DirectiveKind::DoorMap => "links",
+            DirectiveKind::Npc => "npcs",
+            DirectiveKind::Spawn => "spawns",
+            DirectiveKind::Switch => "switches",
+        }
+    }
+}
+
+/// A single parsed directive
+///
+/// * `KeyDoor` - A key and the locked door it opens (`~K`)
+/// * `Treasure` - The items inside a treasure chest (`~T`)
+/// * `DoorMap` - A door leading to another map (`~M`)
+/// * `Npc` - An NPC and how it behaves (`~N`). `stats` are `(max_hp, attack, defense)`
+/// * `Spawn` - A named tile the player can be placed on (`~S`)
+/// * `Switch` - A lever or pressure plate and the tiles it toggles (`~W`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapDirective {
+    KeyDoor(KeyDoorLink),
+    Treasure { coords: WorldCoordinates, items: Vec<(TreasureID, TreasureQuantity)> },
+    DoorMap(DoorMapLink),
+    Npc {
+        coords: WorldCoordinates,
+        name: String,
+        glyph: char,
+        behaviour: Behaviour,
+        stats: Option<(u32, u32, u32)>,
+    },
+    Spawn { coords: WorldCoordinates, name: String },
+    Switch { coords: WorldCoordinates, targets: Vec<WorldCoordinates> },
+}
+
+impl MapDirective {
+    /// ## Returns
+    ///
+    /// The [DirectiveKind] of the directive
+    pub fn kind(&self) -> DirectiveKind {
+        match self {
+            MapDirective::KeyDoor(_) => DirectiveKind::KeyDoor,
+            MapDirective::Treasure { .. } => DirectiveKind::Treasure,
+            MapDirective::DoorMap(_) => DirectiveKind::DoorMap,
+            MapDirective::Npc { .. } => DirectiveKind::Npc,
+            MapDirective::Spawn { .. } => DirectiveKind::Spawn,
+            MapDirective::Switch { .. } => DirectiveKind::Switch,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Parses the directive section of a map file, paired with the ***1-based*** line each directive
+/// was defined on.
+///
+/// # Arguments
+/// * `contents` (`&str`) - The utf8 (lossy) string created from the bytes of a world map txt file.
+/// * `errors` (`&mut Vec<MapParseErrorKind>`) - Collects any malformed directives.
+/// * `warnings` (`&mut Vec<MapParseWarning>`) - Collects any lines which were ignored or
+///   misplaced.
+pub fn parse_directives(
+    contents: &str,
+    errors: &mut Vec<MapParseErrorKind>,
+    warnings: &mut Vec<MapParseWarning>,
+) -> Vec<(usize, MapDirective)> {
+    let mut directives = vec![];
+    // `None` before the first section, `Some(None)` inside an unknown section
+    let mut section: Option<Option<DirectiveKind>> = None;
+
+    let lines = contents.lines()
+        .enumerate()
+        .skip_while(|(_, line)| !line.starts_with(DIRECTIVE_MARKER))
+        .map(|(idx, line)| (idx + 1, line));
+
+    for (line, text) in lines {
+        let tokens = tokenize(text);
+        let malformed = || MapParseErrorKind::MalformedDirective {
+            line, directive: text.trim().to_string()
+        };
+
+        match tokens.as_slice() {
+            [] => {}
+
+            [Token::Tilde, Token::OpenSection, Token::Word(name), Token::CloseSection] => {
+                let kind = DirectiveKind::ALL.into_iter().find(|kind| kind.section() == *name);
+                if kind.is_none() {
+                    warnings.push(MapParseWarning::UnknownSection {
+                        line, name: name.to_string()
+                    });
+                }
+                section = Some(kind);
+            }
+
+            [Token::Tilde, Token::Word(letter), rest @ ..] => {
+                let Some(kind) = DirectiveKind::ALL.into_iter().find(|k| k.letter() == *letter)
+                else {
+                    warnings.push(MapParseWarning::UnknownDirective {
+                        line, directive: text.trim().to_string()
+                    });
+                    continue;
+                };
+
+                let Some(directive) = parse_directive(kind, &mut Cursor::new(rest)) else {
+                    errors.push(malformed());
+                    continue;
+                };
+
+                if let Some(Some(expected)) = section
+                    && expected != kind
+                {
+                    warnings.push(MapParseWarning::MisplacedDirective {
+                        line, directive: text.trim().to_string(), section: expected.section()
+                    });
+                }
+                directives.push((line, directive));
+            }
+
+            _ if text.trim_start().starts_with(DIRECTIVE_MARKER) => errors.push(malformed()),
+
+            _ => warnings.push(MapParseWarning::UnrecognizedLine {
+                line, text: text.trim().to_string()
+            }),
+        }
+    }
+
+    directives
+}
+
+/// Parses everything following the `~` and letter of a directive
+///
+/// ## Returns
+///
+/// The directive, or `None` if it doesn't follow the grammar
+fn parse_directive(kind: DirectiveKind, cursor: &mut Cursor) -> Option<MapDirective> {
+    let coords = cursor.coords()?;
+    cursor.expect(Token::Equals)?;
+
+    let directive = match kind {
+        DirectiveKind::KeyDoor => {
+            cursor.keyword("D")?;
+            MapDirective::KeyDoor(KeyDoorLink { key_coords: coords, door_coords: cursor.coords()? })
+        }
+
+        DirectiveKind::Treasure => {
+            cursor.expect(Token::Open)?;
+            let mut items = vec![];
+            loop {
+                let id = cursor.number()?;
+                cursor.expect(Token::Equals)?;
+                items.push((id, cursor.number()?));
+                match cursor.next()? {
+                    Token::Comma => continue,
+                    Token::Close => break,
+                    _ => return None,
+                }
+            }
+            MapDirective::Treasure { coords, items }
+        }
+
+        DirectiveKind::DoorMap => {
+            let transition_direction = MoveDirection::from_name(cursor.word()?)?;
+            cursor.expect(Token::Arrow)?;
+            let map_id = cursor.number()?;
+            cursor.expect(Token::Open)?;
+            let entry = match cursor.peek()? {
+                Token::Number(_) => {
+                    let row = cursor.number()?;
+                    cursor.expect(Token::Comma)?;
+                    MapEntry::Coords((row, cursor.number()?))
+                }
+                _ => MapEntry::Spawn(cursor.word()?.to_string()),
+            };
+            cursor.expect(Token::Close)?;
+            MapDirective::DoorMap(DoorMapLink {
+                door_coords: coords, transition_direction, map_id, entry
+            })
+        }
+
+        DirectiveKind::Npc => {
+            let name = cursor.word()?.to_string();
+            cursor.expect(Token::Open)?;
+            let glyph = cursor.next()?.as_glyph()?;
+            cursor.expect(Token::Close)?;
+            let behaviour = cursor.word()?;
+
+            let mut route = vec![coords];
+            while cursor.peek() == Some(Token::Open) {
+                route.push(cursor.coords()?);
+            }
+
+            let behaviour = match (behaviour, route.len()) {
+                ("IDLE", 1) => Behaviour::Idle,
+                ("WANDER", 1) => Behaviour::Wander,
+                ("CHASE", 1) => Behaviour::Chase,
+                ("PATROL", 2..) => Behaviour::Patrol { route, next: 1 },
+                _ => return None,
+            };
+
+            let stats = match cursor.peek() {
+                Some(Token::Word("STATS")) => {
+                    cursor.next();
+                    cursor.expect(Token::Open)?;
+                    let hp = cursor.number()?;
+                    cursor.expect(Token::Comma)?;
+                    let attack = cursor.number()?;
+                    cursor.expect(Token::Comma)?;
+                    let defense = cursor.number()?;
+                    cursor.expect(Token::Close)?;
+                    Some((hp, attack, defense))
+                }
+                _ => None,
+            };
+
+            MapDirective::Npc { coords, name, glyph, behaviour, stats }
+        }
+
+        DirectiveKind::Spawn => MapDirective::Spawn { coords, name: cursor.word()?.to_string() },
+
+        DirectiveKind::Switch => {
+            let mut targets = vec![cursor.coords()?];
+            while cursor.peek() == Some(Token::Open) {
+                targets.push(cursor.coords()?);
+            }
+            MapDirective::Switch { coords, targets }
+        }
+    };
+
+    // Anything left over means the line doesn't follow the grammar
+    cursor.peek().is_none().then_some(directive)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A single token of a directive line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Tilde,
+    Open,
+    Close,
+    OpenSection,
+    CloseSection,
+    Comma,
+    Equals,
+    Arrow,
+    Number(&'a str),
+    Word(&'a str),
+    Symbol(char),
+}
+
+impl Token<'_> {
+    /// ## Returns
+    ///
+    /// The single character the token was made from, for use as an NPC glyph
+    fn as_glyph(&self) -> Option<char> {
+        let single = |text: &str| {
+            let mut chars = text.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        };
+
+        match *self {
+            Token::Tilde => Some('~'),
+            Token::OpenSection => Some('['),
+            Token::CloseSection => Some(']'),
+            Token::Comma => Some(','),
+            Token::Equals => Some('='),
+            Token::Number(text) | Token::Word(text) => single(text),
+            Token::Symbol(c) => Some(c),
+            Token::Open | Token::Close | Token::Arrow => None,
+        }
+    }
+}
+
+/// Splits a line into [Token]s, skipping whitespace and stopping at a `//` comment
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '/') => break,
+            '-' if chars.peek().is_some_and(|&(_, next)| next == '>') => {
+                chars.next();
+                Token::Arrow
+            }
+            '~' => Token::Tilde,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::OpenSection,
+            ']' => Token::CloseSection,
+            ',' => Token::Comma,
+            '=' => Token::Equals,
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(idx, next)) = chars.peek()
+                    && (next.is_alphanumeric() || next == '_')
+                {
+                    end = idx + next.len_utf8();
+                    chars.next();
+                }
+
+                let text = &line[start..end];
+                if text.chars().all(|c| c.is_ascii_digit()) {
+                    Token::Number(text)
+                } else {
+                    Token::Word(text)
+                }
+            }
+            c => Token::Symbol(c),
+        };
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Walks through the [Token]s of a directive. Every method returns `None` if the next tokens
+/// aren't what was asked for.
+struct Cursor<'t, 'a> {
+    tokens: &'t [Token<'a>],
+}
+
+impl<'t, 'a> Cursor<'t, 'a> {
+    fn new(tokens: &'t [Token<'a>]) -> Self {
+        Self { tokens }
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.first().copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let (first, rest) = self.tokens.split_first()?;
+        self.tokens = rest;
+        Some(*first)
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        (self.next()? == token).then_some(())
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Option<()> {
+        (self.word()? == keyword).then_some(())
+    }
+
+    fn word(&mut self) -> Option<&'a str> {
+        match self.next()? {
+            Token::Word(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> Option<T> {
+        match self.next()? {
+            Token::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn coords(&mut self) -> Option<WorldCoordinates> {
+        self.expect(Token::Open)?;
+        let row = self.number()?;
+        self.expect(Token::Comma)?;
+        let col = self.number()?;
+        self.expect(Token::Close)?;
+        Some((row, col))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the directives following a single grid line, so the first directive is on line 2
+    fn parse(
+        directives: &str,
+    ) -> (Vec<(usize, MapDirective)>, Vec<MapParseErrorKind>, Vec<MapParseWarning>) {
+        let (mut errors, mut warnings) = (vec![], vec![]);
+        let contents = format!("#.#\n{}", directives);
+        let parsed = parse_directives(&contents, &mut errors, &mut warnings);
+        (parsed, errors, warnings)
+    }
+
+    /// Parses a single directive which is expected to be valid
+    fn parse_one(directive: &str) -> MapDirective {
+        let (mut parsed, errors, warnings) = parse(directive);
+        assert_eq!((errors, warnings), (vec![], vec![]));
+        assert_eq!(parsed.len(), 1);
+        parsed.remove(0).1
+    }
+
+    /// Parses a single directive which is expected to be malformed, returning its error
+    fn parse_malformed(directive: &str) -> MapParseErrorKind {
+        let (parsed, mut errors, _) = parse(directive);
+        assert!(parsed.is_empty());
+        assert_eq!(errors.len(), 1);
+        errors.remove(0)
+    }
+
+    #[test]
+    fn parses_every_directive_kind() {
+        assert_eq!(
+            parse_one("~K(1,2) = D(3,4)"),
+            MapDirective::KeyDoor(KeyDoorLink { key_coords: (1, 2), door_coords: (3, 4) })
+        );
+        assert_eq!(
+            parse_one("~T(1,2) = (1 = 500, 3 = 2)"),
+            MapDirective::Treasure { coords: (1, 2), items: vec![(1, 500), (3, 2)] }
+        );
+        assert_eq!(
+            parse_one("~M(5,0) = LEFT -> 0(east_door)"),
+            MapDirective::DoorMap(DoorMapLink {
+                door_coords: (5, 0),
+                transition_direction: MoveDirection::LEFT,
+                map_id: 0,
+                entry: MapEntry::Spawn("east_door".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_one("~M(5,0)=RIGHT->1(2,3)"),
+            MapDirective::DoorMap(DoorMapLink {
+                door_coords: (5, 0),
+                transition_direction: MoveDirection::RIGHT,
+                map_id: 1,
+                entry: MapEntry::Coords((2, 3)),
+            })
+        );
+        assert_eq!(
+            parse_one("~N(4,12) = Goblin(g) CHASE STATS(8,4,1)"),
+            MapDirective::Npc {
+                coords: (4, 12),
+                name: "Goblin".to_string(),
+                glyph: 'g',
+                behaviour: Behaviour::Chase,
+                stats: Some((8, 4, 1)),
+            }
+        );
+        assert_eq!(
+            parse_one("~N(8,1) = Guard(G) PATROL (8,18) (1,18)"),
+            MapDirective::Npc {
+                coords: (8, 1),
+                name: "Guard".to_string(),
+                glyph: 'G',
+                behaviour: Behaviour::Patrol { route: vec![(8, 1), (8, 18), (1, 18)], next: 1 },
+                stats: None,
+            }
+        );
+        assert_eq!(
+            parse_one("~S(1,2) = start"),
+            MapDirective::Spawn { coords: (1, 2), name: "start".to_string() }
+        );
+        assert_eq!(
+            parse_one("~W(1,2) = (1,4) (3,4)"),
+            MapDirective::Switch { coords: (1, 2), targets: vec![(1, 4), (3, 4)] }
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_each_directive() {
+        let (parsed, errors, warnings) = parse(
+            "~[keys]\n\n~K(1,2) = D(3,4)\n~[spawns]\n~K(5,6) = D(7,8)\n"
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(parsed.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![4, 6]);
+        assert_eq!(warnings, vec![MapParseWarning::MisplacedDirective {
+            line: 6, directive: "~K(5,6) = D(7,8)".to_string(), section: "spawns"
+        }]);
+    }
+
+    #[test]
+    fn missing_close_is_malformed() {
+        assert_eq!(parse_malformed("~K(1,2 = D(3,4)"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~K(1,2 = D(3,4)".to_string()
+        });
+        assert_eq!(parse_malformed("~T(1,2) = (1 = 500"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~T(1,2) = (1 = 500".to_string()
+        });
+    }
+
+    #[test]
+    fn non_numeric_coordinate_is_malformed() {
+        assert_eq!(parse_malformed("~S(1,x) = start"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~S(1,x) = start".to_string()
+        });
+        assert_eq!(parse_malformed("~S(-1,2) = start"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~S(-1,2) = start".to_string()
+        });
+    }
+
+    #[test]
+    fn trailing_comment_is_ignored() {
+        assert_eq!(
+            parse_one("~S(1,2) = start // where the player begins"),
+            MapDirective::Spawn { coords: (1, 2), name: "start".to_string() }
+        );
+
+        // Anything after the comment is never parsed, so the directive is cut short
+        assert_eq!(parse_malformed("~K(1,2) = // D(3,4)"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~K(1,2) = // D(3,4)".to_string()
+        });
+    }
+
+    #[test]
+    fn whole_line_comments_are_ignored() {
+        let (directives, errors, warnings) = parse(concat!(
+            "~[spawns]\n",
+            "// where the player begins\n",
+            "~S(1,2) = start\n",
+            "   // and leaves\n",
+            "~S(1,0) = exit",
+        ));
+
+        assert_eq!(directives, vec![
+            (4, MapDirective::Spawn { coords: (1, 2), name: "start".to_string() }),
+            (6, MapDirective::Spawn { coords: (1, 0), name: "exit".to_string() }),
+        ]);
+        assert_eq!((errors, warnings), (vec![], vec![]));
+    }
+
+    #[test]
+    fn non_ascii_words_and_digits() {
+        assert_eq!(
+            parse_one("~N(1,1) = Größe(ö) IDLE"),
+            MapDirective::Npc {
+                coords: (1, 1),
+                name: "Größe".to_string(),
+                glyph: 'ö',
+                behaviour: Behaviour::Idle,
+                stats: None,
+            }
+        );
+
+        // Only ASCII digits make numbers
+        assert_eq!(parse_malformed("~S(١,2) = start"), MapParseErrorKind::MalformedDirective {
+            line: 2, directive: "~S(١,2) = start".to_string()
+        });
+    }
+}
@@ -0,0 +1,443 @@
+//! # Map Document
+//!
+//! A structured alternative to the ASCII map format, stored as RON. Instead of packing everything
+//! into a grid and `~` directives, a [MapDocument] lists each part of the map as data, so tools
+//! can generate and edit maps without any string munging:
+//!
+//! ```text
+//! (
+//!     meta: (name: "test_map1"),
+//!     legend: { '#': "Wall", '.': "Floor", '|': "Locked Door", 'K': "Key" },
+//!     layers: [ (name: "terrain", rows: ["#####", "#.K|#", "#####"]) ],
+//!     keys: [ (key: (1, 2), door: (1, 3)) ],
+//!     chests: [ (position: (4, 8), items: [(1, 250)]) ],
+//!     links: [ (door: (5, 0), direction: "LEFT", map_id: 0, entry: Spawn("east_door")) ],
+//!     npcs: [ (name: "Rat", glyph: 'r', position: (1, 17), behaviour: "WANDER") ],
+//!     spawns: { "start": (1, 2) },
+//!     switches: [ (switch: (3, 4), targets: [(1, 3)]) ],
+//! )
+//! ```
+//!
+//! Every layer is drawn over the ones before it, with spaces letting the layers below show
+//! through. The characters of the layers are looked up in the `legend`, which maps them to the
+//! names of tiles in the [TileRegistry].
+//!
+//! A document is loaded by rendering it to the equivalent ASCII map (see [MapDocument::to_ascii])
+//! and parsing that, so both formats are validated the same way. Line numbers in the resulting
+//! errors refer to the rendered ASCII map.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
+// > CRATE
+use crate::npc::Behaviour;
+use crate::registry::{Registries, TileRegistry};
+use crate::shared::treasure::{TreasureID, TreasureQuantity};
+use crate::shared::{MapEntry, WorldCoordinates};
+
+// > SUPER
+use super::world_map::extract_map_lines;
+use super::{
+    MapDirective, MapParseError, MapParseErrorKind, WorldMap, is_tiled_map, load_tiled_map,
+    parse_directives,
+};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The file extension of structured map documents
+pub const MAP_DOCUMENT_EXTENSION: &str = "ron";
+
+/// ## Returns
+///
+/// `true` if the map file at `path` is a structured map document rather than an ASCII map
+pub fn is_map_document(path: impl AsRef<Path>) -> bool {
+    path.as_ref().extension().is_some_and(|ext| ext == MAP_DOCUMENT_EXTENSION)
+}
+
+/// Reads the map file at `path` as an ASCII map, converting structured documents (`.ron`) and
+/// Tiled maps (`.tmj` / `.tmx`) to their ASCII form
+///
+/// # Arguments
+/// * `path` ( `impl AsRef<Path>` ) - The path of the map file
+/// * `registries` ( `&Registries` ) - The tile definitions used to convert structured maps
+///
+/// # Errors
+/// Returns a [MapParseError] if the file cannot be read or converted.
+pub fn read_map_source(
+    path: impl AsRef<Path>,
+    registries: &Registries,
+) -> Result<String, MapParseError> {
+    let path = path.as_ref();
+    if is_tiled_map(path) {
+        return load_tiled_map(path, registries)?.to_ascii(&registries.tiles);
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| MapParseError::single(
+        MapParseErrorKind::Unreadable { path: path.display().to_string(), reason: e.to_string() }
+    ))?;
+    let contents = String::from_utf8_lossy(&bytes).into_owned();
+
+    match is_map_document(path) {
+        true => MapDocument::from_ron(&contents)?.to_ascii(&registries.tiles),
+        false => Ok(contents),
+    }
+}
+
+/// A structured map, holding the same information as an ASCII map file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapDocument {
+    pub meta: MapMeta,
+    /// The name of the tile each character of the layers stands for
+    pub legend: BTreeMap<char, String>,
+    /// The grid, drawn bottom layer first
+    pub layers: Vec<MapLayer>,
+    #[serde(default)]
+    pub keys: Vec<KeyEntry>,
+    #[serde(default)]
+    pub chests: Vec<ChestEntry>,
+    #[serde(default)]
+    pub links: Vec<LinkEntry>,
+    #[serde(default)]
+    pub npcs: Vec<NpcEntry>,
+    #[serde(default)]
+    pub spawns: BTreeMap<String, WorldCoordinates>,
+    #[serde(default)]
+    pub switches: Vec<SwitchEntry>,
+}
+
+/// Information about a map which doesn't affect how it plays
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapMeta {
+    pub name: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A named layer of grid rows. Spaces are transparent on every layer but the first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapLayer {
+    pub name: String,
+    pub rows: Vec<String>,
+}
+
+/// A key and the locked door it opens (see `~K` in [map directives](super::parse_directives))
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyEntry {
+    pub key: WorldCoordinates,
+    pub door: WorldCoordinates,
+}
+
+/// The items inside a treasure chest, as `(item id, quantity)` pairs (see `~T`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChestEntry {
+    pub position: WorldCoordinates,
+    pub items: Vec<(TreasureID, TreasureQuantity)>,
+}
+
+/// A door leading to another map (see `~M`). `direction` is `UP`, `DOWN`, `LEFT` or `RIGHT`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkEntry {
+    pub door: WorldCoordinates,
+    pub direction: String,
+    pub map_id: usize,
+    pub entry: MapEntry,
+}
+
+/// An NPC and how it behaves (see `~N`). `behaviour` is `IDLE`, `WANDER`, `CHASE` or `PATROL`,
+/// `route` lists the waypoints of a patrol and `stats` are `(max_hp, attack, defense)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NpcEntry {
+    pub name: String,
+    pub glyph: char,
+    pub position: WorldCoordinates,
+    pub behaviour: String,
+    #[serde(default)]
+    pub route: Vec<WorldCoordinates>,
+    #[serde(default)]
+    pub stats: Option<(u32, u32, u32)>,
+}
+
+/// A lever or pressure plate and the tiles it toggles (see `~W`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchEntry {
+    pub switch: WorldCoordinates,
+    pub targets: Vec<WorldCoordinates>,
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl MapDocument {
+    /// Deserializes a `MapDocument` from RON
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] if the RON is malformed.
+    pub fn from_ron(contents: &str) -> Result<Self, MapParseError> {
+        ron::from_str(contents).map_err(|e| MapParseError::single(
+            MapParseErrorKind::MalformedDocument { reason: e.to_string() }
+        ))
+    }
+
+    /// ## Returns
+    ///
+    /// The `MapDocument` serialized as pretty-printed RON
+    pub fn to_ron(&self) -> String {
+        let config = ron::ser::PrettyConfig::default().struct_names(false);
+        ron::ser::to_string_pretty(self, config)
+            .expect("Map documents only contain types RON can serialize.")
+    }
+
+    /// Converts an ASCII map file into a `MapDocument` with a single `terrain` layer
+    ///
+    /// # Arguments
+    /// * `contents` (`&str`) - The contents of the ASCII map file
+    /// * `name` (`&str`) - The name stored in the document's metadata
+    /// * `registries` (`&Registries`) - The definitions the map is validated against
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] if the ASCII map does not load, as only valid maps are converted.
+    pub fn from_ascii(
+        contents: &str,
+        name: &str,
+        registries: &Registries,
+    ) -> Result<Self, MapParseError> {
+        WorldMap::parse(contents.as_bytes(), 0, registries)?;
+
+        let rows: Vec<String> = extract_map_lines(contents).into_iter()
+            .map(|(_, line)| line.to_string())
+            .collect();
+        let legend = rows.iter()
+            .flat_map(|row| row.chars())
+            .filter_map(|glyph| Some((glyph, registries.tiles.get(glyph)?.name.clone())))
+            .collect();
+
+        let mut document = Self {
+            meta: MapMeta { name: name.to_string(), ..Default::default() },
+            legend,
+            layers: vec![MapLayer { name: "terrain".to_string(), rows }],
+            ..Default::default()
+        };
+
+        for (_, directive) in parse_directives(contents, &mut vec![], &mut vec![]) {
+            match directive {
+                MapDirective::KeyDoor(kdl) => document.keys.push(KeyEntry {
+                    key: kdl.key_coords, door: kdl.door_coords,
+                }),
+                MapDirective::Treasure { coords, items } => {
+                    document.chests.push(ChestEntry { position: coords, items });
+                }
+                MapDirective::DoorMap(dml) => document.links.push(LinkEntry {
+                    door: dml.door_coords,
+                    direction: dml.transition_direction.name().unwrap_or_default().to_string(),
+                    map_id: dml.map_id,
+                    entry: dml.entry,
+                }),
+                MapDirective::Npc { coords, name, glyph, behaviour, stats } => {
+                    let (behaviour, route) = match behaviour {
+                        Behaviour::Idle => ("IDLE", vec![]),
+                        Behaviour::Wander => ("WANDER", vec![]),
+                        Behaviour::Chase => ("CHASE", vec![]),
+                        // The route starts at the NPC's own position, which isn't written out
+                        Behaviour::Patrol { route, .. } => ("PATROL", route[1..].to_vec()),
+                    };
+                    document.npcs.push(NpcEntry {
+                        name, glyph, position: coords, behaviour: behaviour.to_string(), route,
+                        stats,
+                    });
+                }
+                MapDirective::Spawn { coords, name } => {
+                    document.spawns.insert(name, coords);
+                }
+                MapDirective::Switch { coords, targets } => {
+                    document.switches.push(SwitchEntry { switch: coords, targets });
+                }
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// Renders the `MapDocument` as the equivalent ASCII map file
+    ///
+    /// # Arguments
+    /// * `tiles` (`&TileRegistry`) - The tile definitions the legend's names are looked up in
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] if the document has no layers, a layer is wider or taller than
+    /// the first one, or the legend doesn't resolve every character to a known tile. Entities are
+    /// not checked here, but when the rendered map is parsed.
+    pub fn to_ascii(&self, tiles: &TileRegistry) -> Result<String, MapParseError> {
+        let mut errors: Vec<MapParseErrorKind> = vec![];
+
+        let Some((base, overlays)) = self.layers.split_first() else {
+            return Err(MapParseError::single(MapParseErrorKind::MalformedDocument {
+                reason: "map has no layers".to_string()
+            }));
+        };
+
+        // Draw every layer over the first, letting spaces show the tiles below
+        let mut grid: Vec<Vec<char>> = base.rows.iter().map(|row| row.chars().collect()).collect();
+        for layer in overlays {
+            for (row, line) in layer.rows.iter().enumerate() {
+                let Some(grid_row) = grid.get_mut(row) else {
+                    errors.push(MapParseErrorKind::MalformedDocument {
+                        reason: format!("layer `{}` has more rows than `{}`", layer.name, base.name)
+                    });
+                    break;
+                };
+                for (col, glyph) in line.chars().enumerate().filter(|(_, c)| *c != ' ') {
+                    match grid_row.get_mut(col) {
+                        Some(cell) => *cell = glyph,
+                        None => {
+                            errors.push(MapParseErrorKind::RaggedLine {
+                                line: row + 1, expected: grid_row.len(), found: line.chars().count()
+                            });
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Look the legend's names up in the tile registry
+        let mut glyphs: BTreeMap<char, char> = BTreeMap::new();
+        for (character, name) in &self.legend {
+            match tiles.iter().find(|def| &def.name == name) {
+                Some(def) => _ = glyphs.insert(*character, def.glyph),
+                None => errors.push(MapParseErrorKind::UnknownTileName { name: name.clone() }),
+            }
+        }
+
+        let mut ascii = String::new();
+        for (row, line) in grid.iter().enumerate() {
+            for (col, character) in line.iter().enumerate() {
+                match glyphs.get(character) {
+                    Some(glyph) => ascii.push(*glyph),
+                    None => {
+                        if !self.legend.contains_key(character) {
+                            errors.push(MapParseErrorKind::UnknownTile {
+                                line: row + 1, column: col + 1, glyph: *character
+                            });
+                        }
+                        ascii.push(*character);
+                    }
+                }
+            }
+            ascii.push('\n');
+        }
+
+        if !errors.is_empty() {
+            return Err(MapParseError { errors, warnings: vec![] });
+        }
+
+        self.write_directives(&mut ascii);
+        Ok(ascii)
+    }
+
+    /// Appends the entities of the document to `ascii` as `~` directives, one section per kind
+    fn write_directives(&self, ascii: &mut String) {
+        // Writing to a `String` cannot fail
+        let coords = |(row, col): WorldCoordinates| format!("({},{})", row, col);
+
+        if !self.keys.is_empty() {
+            ascii.push_str("~[keys]\n");
+        }
+        for key in &self.keys {
+            _ = writeln!(ascii, "~K{} = D{}", coords(key.key), coords(key.door));
+        }
+
+        if !self.chests.is_empty() {
+            ascii.push_str("~[treasure]\n");
+        }
+        for chest in &self.chests {
+            let items: Vec<String> = chest.items.iter()
+                .map(|(id, qty)| format!("{} = {}", id, qty))
+                .collect();
+            _ = writeln!(ascii, "~T{} = ({})", coords(chest.position), items.join(", "));
+        }
+
+        if !self.links.is_empty() {
+            ascii.push_str("~[links]\n");
+        }
+        for link in &self.links {
+            let entry = match &link.entry {
+                MapEntry::Coords(entry) => coords(*entry),
+                MapEntry::Spawn(name) => format!("({})", name),
+            };
+            _ = writeln!(
+                ascii, "~M{} = {} -> {}{}", coords(link.door), link.direction, link.map_id, entry
+            );
+        }
+
+        if !self.npcs.is_empty() {
+            ascii.push_str("~[npcs]\n");
+        }
+        for npc in &self.npcs {
+            _ = write!(
+                ascii, "~N{} = {}({}) {}", coords(npc.position), npc.name, npc.glyph, npc.behaviour
+            );
+            for waypoint in &npc.route {
+                _ = write!(ascii, " {}", coords(*waypoint));
+            }
+            if let Some((hp, attack, defense)) = npc.stats {
+                _ = write!(ascii, " STATS({},{},{})", hp, attack, defense);
+            }
+            ascii.push('\n');
+        }
+
+        if !self.spawns.is_empty() {
+            ascii.push_str("~[spawns]\n");
+        }
+        for (name, position) in &self.spawns {
+            _ = writeln!(ascii, "~S{} = {}", coords(*position), name);
+        }
+
+        if !self.switches.is_empty() {
+            ascii.push_str("~[switches]\n");
+        }
+        for switch in &self.switches {
+            let targets: Vec<String> = switch.targets.iter()
+                .map(|target| coords(*target))
+                .collect();
+            _ = writeln!(ascii, "~W{} = {}", coords(switch.switch), targets.join(" "));
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exports an ASCII map to RON and loads it back, comparing it with the map parsed directly
+    fn assert_round_trip(path: &str) {
+        let registries = Registries::default();
+        let ascii = std::fs::read_to_string(path).unwrap();
+        let direct = WorldMap::from_bytes(ascii.as_bytes(), 0, &registries).unwrap();
+
+        let document = MapDocument::from_ascii(&ascii, "round_trip", &registries).unwrap();
+        let ron = document.to_ron();
+        assert_eq!(MapDocument::from_ron(&ron).unwrap(), document);
+
+        let (loaded, warnings) = WorldMap::parse_document(ron.as_bytes(), 0, &registries).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(loaded, direct);
+    }
+
+    #[test]
+    fn ascii_maps_round_trip_through_documents() {
+        assert_round_trip("assets/test_map1.txt");
+        assert_round_trip("assets/test_map2.txt");
+    }
+}
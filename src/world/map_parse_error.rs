@@ -0,0 +1,235 @@
+//! # Map Parse Error
+//!
+//! Structured errors produced while loading a [WorldMap](crate::world::WorldMap). Every problem
+//! found in a map file is collected so the caller can report all of them at once.
+//!
+//! Line and column numbers are ***1-based*** and refer to the source map file.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::fmt;
+
+// > CRATE
+use crate::shared::WorldCoordinates;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing a single problem found while parsing a map file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapParseErrorKind {
+    /// The map file could not be opened or read
+    Unreadable { path: String, reason: String },
+    /// A grid line has a different number of tiles than the first grid line
+    RaggedLine { line: usize, expected: usize, found: usize },
+    /// A grid character does not correspond to any known `Tile`
+    UnknownTile { line: usize, column: usize, glyph: char },
+    /// A key tile has no `~K` directive linking it to a door
+    UnlinkedKey { line: usize, column: usize },
+    /// An uncollected treasure tile has no `~T` directive defining its contents
+    UnlinkedTreasure { line: usize, column: usize },
+    /// A `~K`, `~T`, `~M`, `~N`, `~S` or `~W` directive could not be parsed
+    MalformedDirective { line: usize, directive: String },
+    /// A directive points at a tile of the wrong kind (or outside of the grid)
+    UnmatchedDirective { line: usize, directive: String },
+    /// A `~T` directive references a treasure id that does not exist
+    UnknownTreasure { line: usize, directive: String, id: usize },
+    /// The number of keys does not equal the number of locked doors ( not counting doors only
+    /// opened by a `~W` switch )
+    KeyDoorCountMismatch { keys: usize, locked_doors: usize },
+    /// A `~S` directive places a spawn point on a tile the player cannot stand on
+    ImpassableSpawn { line: usize, name: String },
+    /// A `~S` directive reuses the name of an earlier spawn point
+    DuplicateSpawn { line: usize, name: String },
+    /// A structured (RON) map document could not be deserialized or has no layers
+    MalformedDocument { reason: String },
+    /// The legend of a structured map document names a tile the tile registry does not define
+    UnknownTileName { name: String },
+    /// A `~M` directive enters the map it links to outside of its grid or on a tile the player
+    /// cannot stand on (only checked once both maps are loaded)
+    InvalidMapEntry {
+        map_id: usize,
+        door: WorldCoordinates,
+        target: usize,
+        entry: WorldCoordinates,
+    },
+}
+
+impl MapParseErrorKind {
+    /// ## Returns
+    ///
+    /// The ***1-based*** line of the map file the problem was found on, if it has one
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::RaggedLine { line, .. }
+            | Self::UnknownTile { line, .. }
+            | Self::UnlinkedKey { line, .. }
+            | Self::UnlinkedTreasure { line, .. }
+            | Self::MalformedDirective { line, .. }
+            | Self::UnmatchedDirective { line, .. }
+            | Self::UnknownTreasure { line, .. }
+            | Self::ImpassableSpawn { line, .. }
+            | Self::DuplicateSpawn { line, .. } => Some(*line),
+            Self::Unreadable { .. }
+            | Self::KeyDoorCountMismatch { .. }
+            | Self::MalformedDocument { .. }
+            | Self::UnknownTileName { .. }
+            | Self::InvalidMapEntry { .. } => None,
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// The ***1-based*** column of the map file the problem was found on, if it has one
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Self::UnknownTile { column, .. }
+            | Self::UnlinkedKey { column, .. }
+            | Self::UnlinkedTreasure { column, .. } => Some(*column),
+            _ => None,
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// A description of the problem, without its location
+    pub fn message(&self) -> String {
+        match self {
+            Self::Unreadable { path, reason } => {
+                format!("unable to read map file {}: {}", path, reason)
+            }
+            Self::RaggedLine { expected, found, .. } => {
+                format!("grid line has {} tile(s), expected {}", found, expected)
+            }
+            Self::UnknownTile { glyph, .. } => format!("unknown tile character '{}'", glyph),
+            Self::UnlinkedKey { .. } => {
+                "key tile is not linked to a door by a ~K directive".to_string()
+            }
+            Self::UnlinkedTreasure { .. } => {
+                "treasure tile has no ~T directive defining its contents".to_string()
+            }
+            Self::MalformedDirective { directive, .. } => {
+                format!("malformed directive `{}`", directive)
+            }
+            Self::UnmatchedDirective { directive, .. } => {
+                format!("directive `{}` does not point at a matching tile", directive)
+            }
+            Self::UnknownTreasure { directive, id, .. } => {
+                format!("unknown treasure id {} in `{}`", id, directive)
+            }
+            Self::KeyDoorCountMismatch { keys, locked_doors } => {
+                format!("map has {} key(s) but {} locked door(s)", keys, locked_doors)
+            }
+            Self::ImpassableSpawn { name, .. } => {
+                format!("spawn point `{}` is not on a passable tile", name)
+            }
+            Self::DuplicateSpawn { name, .. } => {
+                format!("spawn point `{}` is declared more than once", name)
+            }
+            Self::MalformedDocument { reason } => format!("malformed map document: {}", reason),
+            Self::UnknownTileName { name } => format!("legend names unknown tile `{}`", name),
+            Self::InvalidMapEntry { map_id, door, target, entry } => format!(
+                "door {}:{} of map {} enters map {} at {}:{}, where the player cannot stand",
+                door.0 + 1, door.1 + 1, map_id, target, entry.0 + 1, entry.1 + 1
+            ),
+        }
+    }
+}
+
+impl fmt::Display for MapParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line(), self.column()) {
+            (Some(line), Some(column)) => write!(f, "{}:{}: {}", line, column, self.message()),
+            (Some(line), None) => write!(f, "{}: {}", line, self.message()),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing a line of a map file which was ignored or looks like a mistake, but does
+/// not stop the map from loading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapParseWarning {
+    /// A `~` directive uses a letter no directive kind is known by
+    UnknownDirective { line: usize, directive: String },
+    /// A `~[name]` section header uses a name no directive kind is known by
+    UnknownSection { line: usize, name: String },
+    /// A directive is placed under the section of a different directive kind
+    MisplacedDirective { line: usize, directive: String, section: &'static str },
+    /// A line of the directive section is neither a directive, a section header nor a comment
+    UnrecognizedLine { line: usize, text: String },
+}
+
+impl MapParseWarning {
+    /// ## Returns
+    ///
+    /// The ***1-based*** line of the map file the warning is about
+    pub fn line(&self) -> usize {
+        match self {
+            Self::UnknownDirective { line, .. }
+            | Self::UnknownSection { line, .. }
+            | Self::MisplacedDirective { line, .. }
+            | Self::UnrecognizedLine { line, .. } => *line,
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// A description of the warning, without its location
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnknownDirective { directive, .. } => {
+                format!("unknown directive `{}` is ignored", directive)
+            }
+            Self::UnknownSection { name, .. } => format!("unknown section `[{}]`", name),
+            Self::MisplacedDirective { directive, section, .. } => {
+                format!("directive `{}` does not belong in the `[{}]` section", directive, section)
+            }
+            Self::UnrecognizedLine { text, .. } => {
+                format!("`{}` is not a directive and is ignored", text)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MapParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.line(), self.message())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The error returned when a map fails to load, containing ***every*** problem that was found
+/// and any warnings raised along the way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapParseError {
+    pub errors: Vec<MapParseErrorKind>,
+    pub warnings: Vec<MapParseWarning>,
+}
+
+impl MapParseError {
+    /// Creates a `MapParseError` from a single [MapParseErrorKind]
+    pub fn single(kind: MapParseErrorKind) -> Self {
+        Self { errors: vec![kind], warnings: vec![] }
+    }
+}
+
+impl fmt::Display for MapParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse map ({} error(s))", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "\n  {}", error)?;
+        }
+        for warning in &self.warnings {
+            write!(f, "\n  warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MapParseError {}
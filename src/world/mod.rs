@@ -14,17 +14,44 @@
 //! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
 
 // >> CRATE (RE-EXPORT)
+mod camera;
+pub use camera::*;
+
+mod fov;
+pub use fov::*;
+
+mod map_directives;
+pub use map_directives::*;
+
+mod map_document;
+pub use map_document::*;
+
+mod map_parse_error;
+pub use map_parse_error::*;
+
+mod pathfinding;
+pub use pathfinding::*;
+
+mod solvability;
+pub use solvability::*;
+
+mod tiled;
+pub use tiled::*;
+
 mod world_map;
-pub(crate) use world_map::*;
+pub use world_map::*;
+
+mod world_event;
+pub use world_event::*;
 
 mod world_update;
-pub(crate) use world_update::*;
+pub use world_update::*;
 
 mod world_view;
-pub(crate) use world_view::*;
+pub use world_view::*;
 
 mod world_controller;
-pub(crate) use world_controller::*;
+pub use world_controller::*;
 
 // > CRATE
 use crate::shared::traits::{Moveable, Positionable};
@@ -50,32 +77,74 @@ pub fn translate<'gloop>(
     type Wup = WorldUpdate<WorldUpdateEventType>;
     type Wut = WorldUpdateEventType;
 
+    // STEPPING OFF A LINKED DOOR
+    // -> Moving off a door tile in its DoorMapLink direction transitions the player to the linked
+    // map
+    let current = world.active_map().grid.get(moveable_mut.row(), moveable_mut.col());
+    if moveable_mut.is_player()
+        && let Some(Tile::Door(props, _)) = current
+        && let Some(dml) = props.dml.clone()
+        && dml.transition_direction == direction
+    {
+        world.queue_update(Wup::new(Wut::MapTransition(dml)));
+        return;
+    }
+
     moveable_mut.translate(direction);
     let new_coords = (moveable_mut.new_row(), moveable_mut.new_col());
 
-    match &world.maps[0].grid[new_coords] {
+    let Some(target) = world.active_map().grid.get(new_coords.0, new_coords.1) else {
+        return; // OUT OF BOUNDS = UNPASSABLE
+    };
+
+    // BUMPING INTO AN NPC
+    // -> The player attacks it; anything else is blocked by it
+    if world.active_map().npc_at(new_coords).is_some() {
+        if moveable_mut.is_player() {
+            world.queue_update(Wup::new(Wut::Attack {
+                attacker: Combatant::Player, defender: Combatant::Npc(new_coords)
+            }));
+        }
+        return;
+    }
+
+    match target {
 
-        // MOVING ONTO FLOOR TILE
-        // -> Can contain event, but currently no floor tiles do
-        Tile::Floor(_) => moveable_mut.translate_into(),
+        // MOVING ONTO TERRAIN TILE
+        // -> Allowed only if the tile's definition is passable
+        Tile::Terrain(props) if props.passable => moveable_mut.translate_into(),
 
         // MOVING ONTO KEY TILE
-        // -> Send a key pickup event to the World Controller
+        // -> Send a key pickup event to the World Controller (only the player picks keys up)
         Tile::Key(props) => { 
             moveable_mut.translate_into();
-            if let Some(kdl) = props.kdl {
+            if moveable_mut.is_player()
+                && let Some(kdl) = props.kdl
+            {
                 world.queue_update(
                     Wup::new(Wut::KeyPickup(kdl))
                 );
             }
         },
 
+        // MOVING INTO LEVER TILE
+        // -> The player pulls ( or pushes back ) the lever; anything else is blocked by it
+        Tile::Lever(props, state) if moveable_mut.is_player() => {
+            let active = *state != CommonState::ACTIVE;
+            world.flip_switch(props.world_coordinates, active);
+        }
+
+        // MOVING ONTO PRESSURE PLATE TILE
+        // -> Allowed if the plate is passable. The plate is pressed (and released once stepped
+        // off) the next time the world updates
+        Tile::Plate(props, _) if props.passable => moveable_mut.translate_into(),
+
         // MOVING ONTO DOOR TILE
         // -> Check to see if the player possesses the proper key and allow entry, changing the
-        // tile state
+        // tile state. Locked doors block everything else
         Tile::Door(props, state) => {
             match *state {
-                CommonState::LOCKED => {
+                CommonState::LOCKED if moveable_mut.is_player() => {
                     world.queue_update(Wup::new(Wut::TryOpenDoor(props.world_coordinates)))
                 },
                 CommonState::UNLOCKED => moveable_mut.translate_into(),
@@ -83,7 +152,7 @@ pub fn translate<'gloop>(
             }
         }
         
-        _ => () // WALL OR ANY UNIMPLEMENTED TILE = UNPASSABLE
+        _ => () // IMPASSABLE TERRAIN, TREASURE OR LEVER = UNPASSABLE
     }
 }
 
@@ -119,7 +188,7 @@ pub fn pickup_explicit<'gloop>(
 
     for &[r, c] in &adjacents {
         if  world.within_bounds(&(r as isize), &(c as isize)) 
-            && world.maps[0].grid[(r, c)].get_properties_mut().treasure.is_some() 
+            && world.active_map().grid[(r, c)].get_properties().treasure.is_some() 
         {
             match explicit_pickup_type {
                 ExplicitPickupType::TreasureChest => world.queue_update(
@@ -129,3 +198,147 @@ pub fn pickup_explicit<'gloop>(
         }
     }
 }
+
+/// Gives every NPC on the active map one turn, in the order they were placed. Each NPC's
+/// [Behaviour](crate::npc::Behaviour) decides a direction, which is then moved in through
+/// [translate]. NPCs never step onto the player: hostile NPCs queue an attack on the player
+/// instead, and every other NPC stands still.
+///
+/// # Arguments
+/// * `world`  ( `&mut WorldController` ) - A ***mutable reference*** to the active 
+///   WorldController
+/// * `player` ( `&impl Positionable` ) - An ***immutable reference*** to the player
+/// 
+/// #### Objects must survive for lifetime <'gloop> (one iteration of the game loop)
+pub fn npc_turns<'gloop>(
+    world: &'gloop mut WorldController,
+    player: &'gloop impl Positionable,
+) {
+    let player_coords = (player.row(), player.col());
+
+    for idx in 0..world.active_map().npcs.len() {
+        // Take the NPC out of the map while it moves, leaving the others in place to block it
+        let mut npc = world.active_map_mut().npcs.remove(idx);
+
+        if let Some(direction) = npc.next_move(world.active_map(), player_coords) {
+            let npc_coords = (npc.row(), npc.col());
+            if direction.step_from(npc_coords) != Some(player_coords) {
+                translate(&mut npc, world, direction);
+            } else if npc.is_hostile() {
+                world.queue_update(WorldUpdate::new(WorldUpdateEventType::Attack {
+                    attacker: Combatant::Npc(npc_coords), defender: Combatant::Player
+                }));
+            }
+        }
+
+        world.active_map_mut().npcs.insert(idx, npc);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::npc::{Behaviour, Npc};
+    use crate::player::Player;
+    use crate::shared::{Stats, WorldCoordinates};
+    use crate::shared::extlib::NAVector3;
+
+    /// An empty 5x3 room
+    const ROOM_MAP: &[u8] = b"\
+#######
+#.....#
+#.....#
+#.....#
+#######
+";
+
+    /// Creates a world on [ROOM_MAP] with the player standing on `coords`
+    fn new_world(coords: WorldCoordinates) -> (WorldController, Player) {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(ROOM_MAP, 0, &world.registries).unwrap());
+        let player = Player::new('@', NAVector3::new(coords.1 as i64, coords.0 as i64, 1));
+        (world, player)
+    }
+
+    /// ## Returns
+    ///
+    /// The coordinates of every NPC on the active map, in order
+    fn npc_coords(world: &WorldController) -> Vec<WorldCoordinates> {
+        world.active_map().npcs.iter().map(|npc| (npc.row(), npc.col())).collect()
+    }
+
+    #[test]
+    fn patrolling_npcs_walk_their_route() {
+        let (mut world, player) = new_world((3, 5));
+        let route = vec![(1, 1), (1, 3)];
+        let guard = Npc::new("Guard", 'G', (1, 1), Behaviour::Patrol { route, next: 1 },
+            Stats::new(5, 1, 0), 1);
+        world.active_map_mut().npcs.push(guard);
+
+        npc_turns(&mut world, &player);
+        assert_eq!(npc_coords(&world), [(1, 2)]);
+        npc_turns(&mut world, &player);
+        assert_eq!(npc_coords(&world), [(1, 3)]);
+
+        // Having reached the end of its route, it turns back
+        npc_turns(&mut world, &player);
+        assert_eq!(npc_coords(&world), [(1, 2)]);
+    }
+
+    #[test]
+    fn chasing_npcs_close_in_and_attack() {
+        let (mut world, mut player) = new_world((3, 1));
+        let goblin = Npc::new("Goblin", 'g', (1, 1), Behaviour::Chase, Stats::new(5, 3, 0), 1);
+        world.active_map_mut().npcs.push(goblin);
+
+        npc_turns(&mut world, &player);
+        world.update_world(&mut player);
+        assert_eq!(npc_coords(&world), [(2, 1)]);
+        assert_eq!(player.stats.hp, player.stats.max_hp);
+
+        // Next to the player it attacks instead of stepping onto them
+        npc_turns(&mut world, &player);
+        world.update_world(&mut player);
+        assert_eq!(npc_coords(&world), [(2, 1)]);
+        assert_eq!(player.stats.hp, player.stats.max_hp - 2);
+        assert_eq!((player.row(), player.col()), (3, 1));
+    }
+
+    #[test]
+    fn peaceful_npcs_never_attack() {
+        let (mut world, mut player) = new_world((1, 2));
+        let route = vec![(1, 1), (1, 3)];
+        let guard = Npc::new("Guard", 'G', (1, 1), Behaviour::Patrol { route, next: 1 },
+            Stats::new(5, 3, 0), 1);
+        world.active_map_mut().npcs.push(guard);
+
+        npc_turns(&mut world, &player);
+        world.update_world(&mut player);
+
+        assert_eq!(npc_coords(&world), [(1, 1)]);
+        assert_eq!(player.stats.hp, player.stats.max_hp);
+    }
+
+    #[test]
+    fn defeated_npcs_are_removed_and_reward_xp() {
+        let (mut world, mut player) = new_world((1, 1));
+        let rat = Npc::new("Rat", 'r', (1, 2), Behaviour::Idle, Stats::new(4, 1, 0), 1);
+        let xp = rat.xp_reward();
+        world.active_map_mut().npcs.push(rat);
+
+        // Bumping into the rat attacks it without moving the player
+        translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+        assert_eq!((player.row(), player.col()), (1, 1));
+        assert_eq!(world.active_map().npcs[0].stats.hp, 1);
+        assert_eq!(player.stats.xp, 0);
+
+        translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+        assert!(world.active_map().npcs.is_empty());
+        assert_eq!(player.stats.xp, xp);
+        assert!(world.active_map().is_passable((1, 2)));
+    }
+}
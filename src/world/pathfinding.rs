@@ -0,0 +1,346 @@
+//! # Pathfinding
+//!
+//! Answers "how do I get from A to B" on the `Grid<Tile>` of a [WorldMap]. Two tools are
+//! provided:
+//!
+//! - [find_path] - A* search for a single path between two tiles
+//! - [DijkstraMap] - the walking distance from every tile to the nearest of a set of goals, for
+//!   "which tiles can be reached" questions and for many movers heading to the same goal
+//!
+//! Every step costs 1. Tiles which are not passable (walls, chests, impassable terrain) are never
+//! entered, and locked doors are either impassable or passable at an extra cost depending on the
+//! [PathOptions]. No step costs less than 1, which keeps the A* heuristic admissible. NPCs are not
+//! considered, as they move every turn.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+// > USE 3P
+use grid::Grid;
+
+// > CRATE
+use crate::shared::{CommonState, MoveDirection, Tile, WorldCoordinates};
+
+// > SUPER
+use super::WorldMap;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The rules a path has to follow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathOptions {
+    /// The cost of stepping onto a locked door, or `None` if locked doors are impassable. Costs
+    /// below 1 are raised to 1
+    pub locked_door_cost: Option<u32>,
+}
+
+impl PathOptions {
+    /// Creates `PathOptions` which allow walking through locked doors at the given cost
+    pub fn through_locked_doors(cost: u32) -> Self {
+        Self { locked_door_cost: Some(cost) }
+    }
+
+    /// ## Returns
+    ///
+    /// The cost of stepping onto the `tile`, or `None` if it cannot be entered
+    pub fn step_cost(&self, tile: &Tile) -> Option<u32> {
+        match tile {
+            Tile::Door(_, state) if *state == CommonState::LOCKED => {
+                self.locked_door_cost.map(|cost| cost.max(1))
+            }
+            tile if tile.is_passable() => Some(1),
+            _ => None,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Finds the cheapest path from `from` to `to` using A* search
+///
+/// The destination itself may be impassable (e.g. a chest or a locked door), so a path can end on
+/// the tile being interacted with.
+///
+/// # Arguments
+/// * `map` ( `&WorldMap` ) - The map to search
+/// * `from` ( `WorldCoordinates` ) - The starting tile
+/// * `to` ( `WorldCoordinates` ) - The destination tile
+/// * `options` ( `&PathOptions` ) - The rules the path has to follow
+///
+/// # Returns
+/// The moves leading from `from` to `to` (empty if they are the same tile), or `None` if the
+/// destination cannot be reached
+pub fn find_path(
+    map: &WorldMap,
+    from: WorldCoordinates,
+    to: WorldCoordinates,
+    options: &PathOptions,
+) -> Option<Vec<MoveDirection>> {
+    let grid = &map.grid;
+    grid.get(from.0, from.1)?;
+    grid.get(to.0, to.1)?;
+
+    let mut costs: Grid<Option<u32>> = Grid::new(grid.rows(), grid.cols());
+    let mut came_from: Grid<Option<(WorldCoordinates, MoveDirection)>> =
+        Grid::new(grid.rows(), grid.cols());
+    let mut open = BinaryHeap::new();
+
+    costs[from] = Some(0);
+    open.push(Reverse((manhattan(from, to), 0, from)));
+
+    while let Some(Reverse((_, cost, coords))) = open.pop() {
+        if coords == to {
+            return Some(reconstruct(&came_from, from, to));
+        }
+        // Skip entries superseded by a cheaper route
+        if costs[coords].is_some_and(|best| best < cost) {
+            continue;
+        }
+
+        for direction in MoveDirection::ALL {
+            let Some(next) = direction.step_from(coords) else { continue };
+            let Some(tile) = grid.get(next.0, next.1) else { continue };
+            let Some(step) = options.step_cost(tile).or((next == to).then_some(1)) else {
+                continue;
+            };
+
+            let next_cost = cost + step;
+            if costs[next].is_none_or(|best| next_cost < best) {
+                costs[next] = Some(next_cost);
+                came_from[next] = Some((coords, direction));
+                open.push(Reverse((next_cost + manhattan(next, to), next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Walks `came_from` back from `to` to `from`, returning the moves in walking order
+fn reconstruct(
+    came_from: &Grid<Option<(WorldCoordinates, MoveDirection)>>,
+    from: WorldCoordinates,
+    to: WorldCoordinates,
+) -> Vec<MoveDirection> {
+    let mut moves = vec![];
+    let mut coords = to;
+    while coords != from {
+        let Some((previous, direction)) = came_from[coords] else { break };
+        moves.push(direction);
+        coords = previous;
+    }
+    moves.reverse();
+    moves
+}
+
+/// The Manhattan distance between two tiles, which never overestimates the cost of a path
+fn manhattan(a: WorldCoordinates, b: WorldCoordinates) -> u32 {
+    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u32
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The cost of walking from every tile of a [WorldMap] to the nearest of a set of goal tiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DijkstraMap {
+    distances: Grid<Option<u32>>,
+}
+
+impl DijkstraMap {
+    /// Computes the cost of reaching the nearest goal from every tile of the `map`
+    ///
+    /// # Arguments
+    /// * `map` ( `&WorldMap` ) - The map to search
+    /// * `goals` ( `&[WorldCoordinates]` ) - The goal tiles (distance 0)
+    /// * `options` ( `&PathOptions` ) - The rules paths have to follow
+    pub fn new(map: &WorldMap, goals: &[WorldCoordinates], options: &PathOptions) -> Self {
+        let grid = &map.grid;
+        let mut distances: Grid<Option<u32>> = Grid::new(grid.rows(), grid.cols());
+        let mut open = BinaryHeap::new();
+
+        for &goal in goals {
+            if grid.get(goal.0, goal.1).is_some() {
+                distances[goal] = Some(0);
+                open.push(Reverse((0, goal)));
+            }
+        }
+
+        // Search outwards from the goals. Walking from a tile to its neighbour costs the
+        // neighbour's step cost, so the search pays for the tile it walks away from
+        while let Some(Reverse((distance, coords))) = open.pop() {
+            if distances[coords].is_some_and(|best| best < distance) {
+                continue;
+            }
+            let Some(step) = options.step_cost(&grid[coords]).or((distance == 0).then_some(1))
+            else {
+                continue;
+            };
+
+            for direction in MoveDirection::ALL {
+                let Some(next) = direction.step_from(coords) else { continue };
+                let Some(tile) = grid.get(next.0, next.1) else { continue };
+                if options.step_cost(tile).is_none() {
+                    continue;
+                }
+
+                let next_distance = distance + step;
+                if distances[next].is_none_or(|best| next_distance < best) {
+                    distances[next] = Some(next_distance);
+                    open.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+
+        Self { distances }
+    }
+
+    /// ## Returns
+    ///
+    /// The cost of walking from `coords` to the nearest goal, or `None` if no goal can be reached
+    pub fn distance(&self, coords: WorldCoordinates) -> Option<u32> {
+        self.distances.get(coords.0, coords.1).copied().flatten()
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if a goal can be reached from `coords`
+    pub fn is_reachable(&self, coords: WorldCoordinates) -> bool {
+        self.distance(coords).is_some()
+    }
+
+    /// ## Returns
+    ///
+    /// The first move of the cheapest path from `coords` towards the nearest goal, or `None` if
+    /// `coords` is a goal or cannot reach one
+    pub fn step_from(&self, coords: WorldCoordinates) -> Option<MoveDirection> {
+        let here = self.distance(coords)?;
+        MoveDirection::ALL.into_iter()
+            .filter_map(|direction| {
+                let distance = self.distance(direction.step_from(coords)?)?;
+                (distance < here).then_some((distance, direction))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, direction)| direction)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registries;
+
+    /// A wall splitting the bottom row, so (3,1) has to walk around it to reach (3,5)
+    const WALL_MAP: &[u8] = b"\
+#######
+#.....#
+#.###.#
+#..#..#
+#######
+";
+
+    /// A locked door at (1,4) as a shortcut to (1,5), with a ten step detour around the bottom
+    const DOOR_MAP: &[u8] = b"\
+#######
+#.K.|.#
+#.###.#
+#.....#
+#######
+~[keys]
+~K(1,2) = D(1,4)
+";
+
+    /// Parses a map with the built-in definitions
+    fn parse(bytes: &[u8]) -> WorldMap {
+        WorldMap::from_bytes(bytes, 0, &Registries::default()).unwrap()
+    }
+
+    /// Follows `moves` from `from`, returning every tile stepped on
+    fn walk(from: WorldCoordinates, moves: &[MoveDirection]) -> Vec<WorldCoordinates> {
+        moves.iter()
+            .scan(from, |coords, direction| {
+                *coords = direction.step_from(*coords)?;
+                Some(*coords)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shortest_path_goes_around_walls() {
+        let map = parse(WALL_MAP);
+
+        let path = find_path(&map, (3, 1), (3, 5), &PathOptions::default()).unwrap();
+        let steps = walk((3, 1), &path);
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(steps.last(), Some(&(3, 5)));
+        assert!(steps.iter().all(|&coords| map.grid[coords].is_passable()));
+        assert_eq!(find_path(&map, (3, 1), (3, 1), &PathOptions::default()), Some(vec![]));
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let map = parse(b"#######\n#..#..#\n#######\n");
+
+        assert_eq!(find_path(&map, (1, 1), (1, 4), &PathOptions::default()), None);
+        assert_eq!(find_path(&map, (1, 1), (9, 9), &PathOptions::default()), None);
+
+        let dijkstra = DijkstraMap::new(&map, &[(1, 4)], &PathOptions::default());
+        assert!(!dijkstra.is_reachable((1, 1)));
+        assert_eq!(dijkstra.step_from((1, 1)), None);
+    }
+
+    #[test]
+    fn locked_doors_cost_extra_to_walk_through() {
+        let map = parse(DOOR_MAP);
+
+        let around = find_path(&map, (1, 3), (1, 5), &PathOptions::default()).unwrap();
+        assert_eq!(around.len(), 10);
+
+        let through = find_path(&map, (1, 3), (1, 5), &PathOptions::through_locked_doors(3));
+        assert_eq!(through, Some(vec![MoveDirection::RIGHT, MoveDirection::RIGHT]));
+
+        // A door costing more than the detour is walked around
+        let expensive = find_path(&map, (1, 3), (1, 5), &PathOptions::through_locked_doors(20));
+        assert_eq!(expensive.map(|path| path.len()), Some(10));
+
+        let dijkstra = DijkstraMap::new(&map, &[(1, 5)], &PathOptions::through_locked_doors(3));
+        assert_eq!(dijkstra.distance((1, 3)), Some(4));
+    }
+
+    #[test]
+    fn free_doors_still_cost_a_step() {
+        let map = parse(DOOR_MAP);
+        let free = PathOptions::through_locked_doors(0);
+
+        assert_eq!(free.step_cost(&map.grid[(1, 4)]), Some(1));
+        assert_eq!(
+            find_path(&map, (1, 3), (1, 5), &free),
+            Some(vec![MoveDirection::RIGHT, MoveDirection::RIGHT])
+        );
+        assert_eq!(DijkstraMap::new(&map, &[(1, 5)], &free).distance((1, 3)), Some(2));
+    }
+
+    #[test]
+    fn dijkstra_steps_descend_towards_the_goal() {
+        let map = parse(WALL_MAP);
+        let dijkstra = DijkstraMap::new(&map, &[(3, 5)], &PathOptions::default());
+
+        assert_eq!(dijkstra.distance((3, 1)), Some(8));
+        assert_eq!(dijkstra.step_from((3, 5)), None);
+
+        let mut coords = (3, 1);
+        while let Some(direction) = dijkstra.step_from(coords) {
+            let next = direction.step_from(coords).unwrap();
+            assert_eq!(dijkstra.distance(next), dijkstra.distance(coords).map(|d| d - 1));
+            coords = next;
+        }
+        assert_eq!(coords, (3, 5));
+    }
+}
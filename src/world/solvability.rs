@@ -0,0 +1,365 @@
+//! # Solvability
+//!
+//! Checks that the key/door puzzles of a [WorldMap] can be solved from where the player starts.
+//!
+//! The check simulates the player exploring the map: every tile reachable from the start is
+//! visited, every key on those tiles is collected, and every locked door next to them whose key
+//! has been collected is opened, as is every locked door linked to a lever the player can walk up
+//! to. This repeats until nothing new opens up, after which anything the player never reached is
+//! reported as a [SolvabilityIssue]. Pressure plates are not considered, as the door they open
+//! closes again once the player steps off.
+//!
+//! Line and column numbers in messages are ***1-based*** and refer to the source map file.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::fmt;
+
+// > CRATE
+use crate::shared::{CommonState, KeyDoorLink, MoveDirection, Tile, WorldCoordinates};
+
+// > SUPER
+use super::{DijkstraMap, PathOptions, WorldMap, find_path};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` describing a single reason a map cannot be completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolvabilityIssue {
+    /// The player would start outside of the grid or on a tile they cannot stand on
+    InvalidStart { start: WorldCoordinates },
+    /// A key is walled off, even if every locked door were open
+    UnreachableKey { key: WorldCoordinates, door: WorldCoordinates },
+    /// A key can only be reached by walking through the door it opens
+    KeyBehindOwnDoor { key: WorldCoordinates, door: WorldCoordinates },
+    /// A key is locked behind doors whose own keys can never be collected
+    Deadlock { key: WorldCoordinates, door: WorldCoordinates, blocked_by: Vec<WorldCoordinates> },
+    /// A door can never be walked up to, even though nothing else stops it from being opened
+    UnreachableDoor { door: WorldCoordinates },
+    /// A treasure chest can never be walked up to
+    UnreachableChest { chest: WorldCoordinates },
+}
+
+impl SolvabilityIssue {
+    /// ## Returns
+    ///
+    /// The tile the issue is about
+    pub fn coords(&self) -> WorldCoordinates {
+        match self {
+            Self::InvalidStart { start } => *start,
+            Self::UnreachableKey { key, .. }
+            | Self::KeyBehindOwnDoor { key, .. }
+            | Self::Deadlock { key, .. } => *key,
+            Self::UnreachableDoor { door } => *door,
+            Self::UnreachableChest { chest } => *chest,
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// A description of the issue, without the location of the tile it is about
+    pub fn message(&self) -> String {
+        match self {
+            Self::InvalidStart { .. } => "the player cannot start on this tile".to_string(),
+            Self::UnreachableKey { door, .. } => {
+                format!("key for the door at {} can never be reached", at(*door))
+            }
+            Self::KeyBehindOwnDoor { door, .. } => {
+                format!("key is sealed behind the door it opens at {}", at(*door))
+            }
+            Self::Deadlock { door, blocked_by, .. } => format!(
+                "key for the door at {} is locked behind door(s) which never open: {}",
+                at(*door),
+                blocked_by.iter().map(|coords| at(*coords)).collect::<Vec<_>>().join(", ")
+            ),
+            Self::UnreachableDoor { .. } => "door can never be reached".to_string(),
+            Self::UnreachableChest { .. } => "treasure chest can never be reached".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SolvabilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", at(self.coords()), self.message())
+    }
+}
+
+/// Formats grid coordinates as a ***1-based*** `line:column`
+fn at(coords: WorldCoordinates) -> String {
+    format!("{}:{}", coords.0 + 1, coords.1 + 1)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The error returned when a map cannot be completed, containing ***every*** issue that was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvabilityError {
+    pub map_id: usize,
+    pub issues: Vec<SolvabilityIssue>,
+}
+
+impl fmt::Display for SolvabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "map {} cannot be completed ({} issue(s))", self.map_id, self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SolvabilityError {}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Checks that every key, door and treasure chest of the `map` can be reached from `start`
+///
+/// # Arguments
+/// * `map` ( `&WorldMap` ) - The map to check
+/// * `start` ( `WorldCoordinates` ) - Where the player enters the map
+///
+/// # Errors
+/// Returns a [SolvabilityError] containing ***every*** issue found on the map.
+pub fn check_solvability(map: &WorldMap, start: WorldCoordinates) -> Result<(), SolvabilityError> {
+    let fail = |issues| Err(SolvabilityError { map_id: map.id, issues });
+    if !map.is_passable(start) {
+        return fail(vec![SolvabilityIssue::InvalidStart { start }]);
+    }
+
+    let links: Vec<KeyDoorLink> = map.grid.iter()
+        .filter_map(|tile| match tile {
+            Tile::Key(props) => props.kdl,
+            _ => None,
+        })
+        .collect();
+    let levers: Vec<(WorldCoordinates, &[WorldCoordinates])> = map.grid.indexed_iter()
+        .filter_map(|(coords, tile)| match tile {
+            Tile::Lever(props, _) => Some((coords, props.switch_targets.as_slice())),
+            _ => None,
+        })
+        .collect();
+
+    // Explore, collect and unlock until nothing new opens up
+    let mut explored = map.clone();
+    let reachable = loop {
+        let reachable = DijkstraMap::new(&explored, &[start], &PathOptions::default());
+        let unlocked = links.iter()
+            .filter(|kdl| reachable.is_reachable(kdl.key_coords))
+            .map(|kdl| kdl.door_coords)
+            .filter(|&door| is_adjacent(&reachable, door));
+        let switched = levers.iter()
+            .filter(|(lever, _)| is_adjacent(&reachable, *lever))
+            .flat_map(|(_, targets)| targets.iter().copied());
+        let openable: Vec<WorldCoordinates> = unlocked.chain(switched)
+            .filter(|&door| is_locked(&explored, door))
+            .collect();
+
+        if openable.is_empty() {
+            break reachable;
+        }
+        for door in openable {
+            explored.grid[door].change_state(CommonState::UNLOCKED);
+        }
+    };
+
+    let mut issues = vec![];
+    for kdl in &links {
+        let (key, door) = (kdl.key_coords, kdl.door_coords);
+        if reachable.is_reachable(key) {
+            if is_locked(&explored, door) {
+                issues.push(SolvabilityIssue::UnreachableDoor { door });
+            }
+            continue;
+        }
+
+        issues.push(match blocking_doors(&explored, start, key) {
+            None => SolvabilityIssue::UnreachableKey { key, door },
+            Some(blocked_by) if blocked_by == [door] => {
+                SolvabilityIssue::KeyBehindOwnDoor { key, door }
+            }
+            Some(blocked_by) => SolvabilityIssue::Deadlock { key, door, blocked_by },
+        });
+    }
+
+    // Doors leading to other maps have to be walked onto, not just up to
+    for (coords, tile) in map.grid.indexed_iter() {
+        match tile {
+            Tile::Door(props, _) if props.dml.is_some() && !reachable.is_reachable(coords) => {
+                issues.push(SolvabilityIssue::UnreachableDoor { door: coords });
+            }
+            Tile::Treasure(_, state)
+                if *state == CommonState::UNCOLLECTED && !is_adjacent(&reachable, coords) =>
+            {
+                issues.push(SolvabilityIssue::UnreachableChest { chest: coords });
+            }
+            _ => {}
+        }
+    }
+
+    if issues.is_empty() { Ok(()) } else { fail(issues) }
+}
+
+/// ## Returns
+///
+/// `true` if the tile at `coords` is a locked door
+fn is_locked(map: &WorldMap, coords: WorldCoordinates) -> bool {
+    matches!(map.grid.get(coords.0, coords.1), Some(Tile::Door(_, CommonState::LOCKED)))
+}
+
+/// ## Returns
+///
+/// `true` if the player can stand next to `coords`
+fn is_adjacent(reachable: &DijkstraMap, coords: WorldCoordinates) -> bool {
+    MoveDirection::ALL.into_iter()
+        .filter_map(|direction| direction.step_from(coords))
+        .any(|neighbour| reachable.is_reachable(neighbour))
+}
+
+/// Finds the path from `start` to `target` crossing the fewest locked doors
+///
+/// ## Returns
+///
+/// The locked doors along that path, or `None` if `target` is walled off entirely
+fn blocking_doors(
+    map: &WorldMap,
+    start: WorldCoordinates,
+    target: WorldCoordinates,
+) -> Option<Vec<WorldCoordinates>> {
+    // Costing a door more than any door-free path means the fewest doors are crossed
+    let door_cost = (map.grid.rows() * map.grid.cols()) as u32;
+    let path = find_path(map, start, target, &PathOptions::through_locked_doors(door_cost))?;
+
+    let mut coords = start;
+    let mut doors = vec![];
+    for direction in path {
+        coords = direction.step_from(coords)?;
+        if is_locked(map, coords) {
+            doors.push(coords);
+        }
+    }
+    Some(doors)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registries;
+
+    /// Parses a map with the built-in definitions
+    fn parse(bytes: &[u8]) -> WorldMap {
+        WorldMap::from_bytes(bytes, 0, &Registries::default()).unwrap()
+    }
+
+    #[test]
+    fn key_door_chain_is_solvable() {
+        // Each key opens the door in front of the next one, and the last door guards a chest
+        let map = parse(b"\
+##########
+#.K.|K.|D#
+##########
+~[keys]
+~K(1,2) = D(1,4)
+~K(1,5) = D(1,7)
+~[treasure]
+~T(1,8) = (1 = 10)
+");
+
+        assert_eq!(check_solvability(&map, (1, 1)), Ok(()));
+    }
+
+    #[test]
+    fn key_behind_its_own_door_is_reported() {
+        let map = parse(b"\
+#######
+#..|K.#
+#######
+~[keys]
+~K(1,4) = D(1,3)
+");
+
+        assert_eq!(check_solvability(&map, (1, 1)), Err(SolvabilityError {
+            map_id: 0,
+            issues: vec![SolvabilityIssue::KeyBehindOwnDoor { key: (1, 4), door: (1, 3) }],
+        }));
+    }
+
+    #[test]
+    fn walled_off_key_is_reported() {
+        let map = parse(b"\
+########
+#..|.#K#
+########
+~[keys]
+~K(1,6) = D(1,3)
+");
+
+        assert_eq!(check_solvability(&map, (1, 1)), Err(SolvabilityError {
+            map_id: 0,
+            issues: vec![SolvabilityIssue::UnreachableKey { key: (1, 6), door: (1, 3) }],
+        }));
+    }
+
+    #[test]
+    fn keys_locked_behind_each_others_doors_are_reported() {
+        // Each key is behind the door the other key opens
+        let map = parse(b"\
+#######
+#K|.|K#
+#######
+~[keys]
+~K(1,1) = D(1,4)
+~K(1,5) = D(1,2)
+");
+
+        assert_eq!(check_solvability(&map, (1, 3)), Err(SolvabilityError {
+            map_id: 0,
+            issues: vec![
+                SolvabilityIssue::Deadlock { key: (1, 1), door: (1, 4), blocked_by: vec![(1, 2)] },
+                SolvabilityIssue::Deadlock { key: (1, 5), door: (1, 2), blocked_by: vec![(1, 4)] },
+            ],
+        }));
+    }
+
+    #[test]
+    fn walled_off_chest_is_reported() {
+        let map = parse(b"\
+######
+#..#D#
+######
+~[treasure]
+~T(1,4) = (1 = 10)
+");
+
+        assert_eq!(check_solvability(&map, (1, 1)), Err(SolvabilityError {
+            map_id: 0,
+            issues: vec![SolvabilityIssue::UnreachableChest { chest: (1, 4) }],
+        }));
+    }
+
+    #[test]
+    fn walled_off_doors_are_reported() {
+        // The key can be collected, but neither its door nor the map link can be walked up to
+        let map = parse(b"\
+#########
+#.K.#|.\\#
+#########
+~[keys]
+~K(1,2) = D(1,5)
+~[links]
+~M(1,7) = RIGHT -> 1(start)
+");
+
+        assert_eq!(check_solvability(&map, (1, 1)), Err(SolvabilityError {
+            map_id: 0,
+            issues: vec![
+                SolvabilityIssue::UnreachableDoor { door: (1, 5) },
+                SolvabilityIssue::UnreachableDoor { door: (1, 7) },
+            ],
+        }));
+    }
+}
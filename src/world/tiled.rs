@@ -0,0 +1,658 @@
+//! # Tiled
+//!
+//! Imports maps made in the [Tiled](https://www.mapeditor.org/) editor, saved as JSON (`.tmj`) or
+//! XML (`.tmx`). A Tiled map is converted to a [MapDocument], so it loads into exactly the
+//! [WorldMap](crate::world::WorldMap) the equivalent ASCII map would.
+//!
+//! ## Conventions
+//!
+//! Every tile used on a tile layer needs a string property called `tile`, holding the name of a
+//! tile in the [TileRegistry](crate::registry::TileRegistry) (e.g. `Locked Door`). Tile layers are
+//! drawn in order, with empty tiles letting the layers below show through. Tilesets may be
+//! embedded or external (`.tsj` / `.tsx`), and tile layer data must be stored uncompressed (JSON
+//! arrays or CSV).
+//!
+//! Objects on object layers are told apart by their class (or type), and placed on the tile they
+//! start on:
+//!
+//! * `key` - A key. Its `door` object property points at the `door` object it opens
+//! * `door` - A door tile. A door with `map_id` (int), `direction` (`UP`, `DOWN`, `LEFT` or
+//!   `RIGHT`) and `entry` (a spawn point name or `row,col`) properties leads to another map
+//! * `chest` - A treasure chest. Its `items` property lists the contents as `id = quantity, ...`
+//! * `spawn` - A spawn point, named after the object
+//! * `switch` - A lever or pressure plate. Its `targets` property lists the ids of the `door` or
+//!   `switch` objects it toggles, separated by commas
+//! * `npc` - An NPC, named after the object, with a `glyph`, a `behaviour` (`IDLE`, `WANDER`,
+//!   `CHASE` or `PATROL`), and optional `route` (`row,col row,col ...`) and `stats`
+//!   (`max_hp,attack,defense`) properties
+//!
+//! Objects without a class are ignored.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > USE
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// > USE 3P
+use serde::Deserialize;
+
+// > CRATE
+use crate::registry::Registries;
+use crate::shared::{MapEntry, WorldCoordinates};
+
+// > SUPER
+use super::{
+    ChestEntry, KeyEntry, LinkEntry, MapDocument, MapLayer, MapMeta, MapParseError,
+    MapParseErrorKind, NpcEntry, SwitchEntry,
+};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The file extensions of Tiled maps
+pub const TILED_EXTENSIONS: [&str; 2] = ["tmj", "tmx"];
+
+/// The bits of a Tiled gid which flag flipped or rotated tiles
+const GID_FLAGS: u32 = 0xF000_0000;
+
+/// ## Returns
+///
+/// `true` if the map file at `path` is a Tiled map
+pub fn is_tiled_map(path: impl AsRef<Path>) -> bool {
+    path.as_ref().extension().is_some_and(|ext| TILED_EXTENSIONS.iter().any(|t| ext == *t))
+}
+
+/// Loads a Tiled map from disk and converts it to a [MapDocument]
+///
+/// # Arguments
+/// * `path` ( `impl AsRef<Path>` ) - The path of the `.tmj` or `.tmx` file
+/// * `registries` ( `&Registries` ) - The tile definitions the `tile` properties are looked up in
+///
+/// # Errors
+/// Returns a [MapParseError] if the map or one of its external tilesets cannot be read, or
+/// doesn't follow the conventions above.
+pub fn load_tiled_map(
+    path: impl AsRef<Path>,
+    registries: &Registries,
+) -> Result<MapDocument, MapParseError> {
+    let path = path.as_ref();
+    let contents = read(path)?;
+    let map = match path.extension().is_some_and(|ext| ext == "tmx") {
+        true => parse_xml_map(&contents, path)?,
+        false => parse_json_map(&contents, path)?,
+    };
+
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    map.into_document(&name, registries)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A Tiled map, whichever format it was read from
+#[derive(Debug, Default)]
+struct TiledMap {
+    width: usize,
+    tile_width: f64,
+    tile_height: f64,
+    tilesets: Vec<TiledTileset>,
+    layers: Vec<TiledLayer>,
+    objects: Vec<TiledObject>,
+}
+
+/// The `tile` property of every tile of a tileset which has one, by local tile id
+#[derive(Debug, Default)]
+struct TiledTileset {
+    first_gid: u32,
+    tiles: BTreeMap<u32, String>,
+}
+
+#[derive(Debug, Default)]
+struct TiledLayer {
+    name: String,
+    gids: Vec<u32>,
+}
+
+/// A Tiled object, with every property value converted to a string
+#[derive(Debug, Default)]
+struct TiledObject {
+    id: u32,
+    name: String,
+    class: String,
+    x: f64,
+    y: f64,
+    gid: Option<u32>,
+    properties: BTreeMap<String, String>,
+}
+
+impl TiledMap {
+    /// Converts the map to a [MapDocument], resolving tiles and objects by the conventions
+    fn into_document(
+        self,
+        name: &str,
+        registries: &Registries,
+    ) -> Result<MapDocument, MapParseError> {
+        let mut document = MapDocument {
+            meta: MapMeta { name: name.to_string(), ..Default::default() },
+            ..Default::default()
+        };
+
+        for layer in &self.layers {
+            let mut rows = vec![];
+            for chunk in layer.gids.chunks(self.width.max(1)) {
+                let mut row = String::new();
+                for gid in chunk {
+                    match gid & !GID_FLAGS {
+                        0 => row.push(' '),
+                        gid => {
+                            let tile = self.tile_name(gid).ok_or_else(|| malformed(format!(
+                                "tile {} on layer `{}` has no `tile` property", gid, layer.name
+                            )))?;
+                            let def = registries.tiles.iter()
+                                .find(|def| def.name == tile)
+                                .ok_or_else(|| MapParseError::single(
+                                    MapParseErrorKind::UnknownTileName { name: tile.to_string() }
+                                ))?;
+                            document.legend.insert(def.glyph, def.name.clone());
+                            row.push(def.glyph);
+                        }
+                    }
+                }
+                rows.push(row);
+            }
+            document.layers.push(MapLayer { name: layer.name.clone(), rows });
+        }
+
+        for object in &self.objects {
+            let position = self.tile_of(object);
+            match object.class.as_str() {
+                "" => {}
+                "key" => {
+                    let door = object.property("door")?.parse::<u32>().ok()
+                        .and_then(|id| self.objects.iter().find(|other| other.id == id))
+                        .ok_or_else(|| malformed(format!(
+                            "key object {} does not point at a door object", object.id
+                        )))?;
+                    document.keys.push(KeyEntry { key: position, door: self.tile_of(door) });
+                }
+                "door" if object.properties.contains_key("map_id") => {
+                    let entry = object.property("entry")?;
+                    document.links.push(LinkEntry {
+                        door: position,
+                        direction: object.property("direction")?.to_string(),
+                        map_id: object.property("map_id")?.parse().map_err(|_| malformed(format!(
+                            "door object {} has an invalid `map_id`", object.id
+                        )))?,
+                        entry: match parse_coords(entry) {
+                            Some(coords) => MapEntry::Coords(coords),
+                            None => MapEntry::Spawn(entry.to_string()),
+                        },
+                    });
+                }
+                "door" => {}
+                "chest" => {
+                    let items = object.property("items")?.split(',')
+                        .map(|item| {
+                            let (id, qty) = item.split_once('=')?;
+                            Some((id.trim().parse().ok()?, qty.trim().parse().ok()?))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| malformed(format!(
+                            "chest object {} has malformed `items`", object.id
+                        )))?;
+                    document.chests.push(ChestEntry { position, items });
+                }
+                "spawn" => {
+                    document.spawns.insert(object.name.clone(), position);
+                }
+                "switch" => {
+                    let targets = object.property("targets")?.split(',')
+                        .map(|id| {
+                            let id = id.trim().parse::<u32>().ok()?;
+                            self.objects.iter().find(|other| other.id == id)
+                        })
+                        .map(|target| target.map(|target| self.tile_of(target)))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| malformed(format!(
+                            "switch object {} has malformed `targets`", object.id
+                        )))?;
+                    document.switches.push(SwitchEntry { switch: position, targets });
+                }
+                "npc" => {
+                    let invalid = |property: &str| malformed(format!(
+                        "npc object {} has an invalid `{}`", object.id, property
+                    ));
+                    let route = match object.properties.get("route") {
+                        Some(route) => route.split_whitespace()
+                            .map(parse_coords)
+                            .collect::<Option<Vec<_>>>()
+                            .ok_or_else(|| invalid("route"))?,
+                        None => vec![],
+                    };
+                    let stats = match object.properties.get("stats") {
+                        Some(stats) => {
+                            let values: Vec<u32> = stats.split(',')
+                                .map(|value| value.trim().parse().ok())
+                                .collect::<Option<_>>()
+                                .ok_or_else(|| invalid("stats"))?;
+                            let [hp, attack, defense] = values[..] else {
+                                return Err(invalid("stats"));
+                            };
+                            Some((hp, attack, defense))
+                        }
+                        None => None,
+                    };
+
+                    document.npcs.push(NpcEntry {
+                        name: object.name.clone(),
+                        glyph: object.property("glyph")?.chars().next()
+                            .ok_or_else(|| invalid("glyph"))?,
+                        position,
+                        behaviour: object.properties.get("behaviour")
+                            .cloned()
+                            .unwrap_or_else(|| "IDLE".to_string()),
+                        route,
+                        stats,
+                    });
+                }
+                class => {
+                    return Err(malformed(format!(
+                        "object {} has unknown class `{}`", object.id, class
+                    )));
+                }
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// ## Returns
+    ///
+    /// The `tile` property of the tile with the given gid, if it has one
+    fn tile_name(&self, gid: u32) -> Option<&str> {
+        let tileset = self.tilesets.iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)?;
+        tileset.tiles.get(&(gid - tileset.first_gid)).map(String::as_str)
+    }
+
+    /// ## Returns
+    ///
+    /// The tile an object starts on. Tile objects are anchored at their bottom-left corner,
+    /// every other object at its top-left corner
+    fn tile_of(&self, object: &TiledObject) -> WorldCoordinates {
+        let mut row = (object.y / self.tile_height).floor().max(0.0) as usize;
+        if object.gid.is_some() {
+            row = row.saturating_sub(1);
+        }
+        (row, (object.x / self.tile_width).floor().max(0.0) as usize)
+    }
+}
+
+impl TiledObject {
+    /// ## Returns
+    ///
+    /// The value of a property the object must have
+    fn property(&self, name: &str) -> Result<&str, MapParseError> {
+        self.properties.get(name).map(String::as_str).ok_or_else(|| malformed(format!(
+            "{} object {} is missing the `{}` property", self.class, self.id, name
+        )))
+    }
+}
+
+/// Parses `row,col` coordinates
+fn parse_coords(text: &str) -> Option<WorldCoordinates> {
+    let (row, col) = text.split_once(',')?;
+    Some((row.trim().parse().ok()?, col.trim().parse().ok()?))
+}
+
+/// Creates a [MapParseError] for a Tiled map which doesn't follow the conventions
+fn malformed(reason: impl Into<String>) -> MapParseError {
+    MapParseError::single(MapParseErrorKind::MalformedDocument { reason: reason.into() })
+}
+
+/// Reads a Tiled map or tileset from disk
+fn read(path: &Path) -> Result<String, MapParseError> {
+    std::fs::read_to_string(path).map_err(|e| MapParseError::single(
+        MapParseErrorKind::Unreadable { path: path.display().to_string(), reason: e.to_string() }
+    ))
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+// JSON (.tmj / .tsj)
+
+#[derive(Deserialize)]
+struct JsonMap {
+    width: usize,
+    tilewidth: f64,
+    tileheight: f64,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    encoding: Option<String>,
+    #[serde(default)]
+    data: Vec<u32>,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    tiles: Vec<JsonTile>,
+}
+
+#[derive(Deserialize)]
+struct JsonTile {
+    id: u32,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+/// Converts JSON properties to strings
+fn json_properties(properties: Vec<JsonProperty>) -> BTreeMap<String, String> {
+    properties.into_iter()
+        .map(|property| {
+            let value = match property.value {
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            };
+            (property.name, value)
+        })
+        .collect()
+}
+
+/// Parses a `.tmj` map. External tilesets are looked up relative to `path`
+fn parse_json_map(contents: &str, path: &Path) -> Result<TiledMap, MapParseError> {
+    let json: JsonMap = serde_json::from_str(contents).map_err(|e| malformed(e.to_string()))?;
+    if json.infinite {
+        return Err(malformed("infinite maps are not supported"));
+    }
+
+    let mut map = TiledMap {
+        width: json.width,
+        tile_width: json.tilewidth,
+        tile_height: json.tileheight,
+        ..Default::default()
+    };
+
+    for tileset in json.tilesets {
+        let tiles = match &tileset.source {
+            Some(source) => load_external_tileset(&path.with_file_name(source))?,
+            None => json_tiles(tileset.tiles),
+        };
+        map.tilesets.push(TiledTileset { first_gid: tileset.firstgid, tiles });
+    }
+
+    // Layer groups are flattened, keeping their layers in drawing order
+    let mut layers: Vec<JsonLayer> = json.layers.into_iter().rev().collect();
+    while let Some(mut layer) = layers.pop() {
+        match layer.kind.as_str() {
+            "tilelayer" => {
+                if layer.encoding.as_deref().is_some_and(|encoding| encoding != "csv") {
+                    return Err(malformed(format!(
+                        "layer `{}` must be stored as CSV, not compressed", layer.name
+                    )));
+                }
+                map.layers.push(TiledLayer { name: layer.name, gids: layer.data });
+            }
+            "objectgroup" => map.objects.extend(layer.objects.into_iter().map(|object| {
+                TiledObject {
+                    id: object.id,
+                    name: object.name,
+                    class: if object.class.is_empty() { object.kind } else { object.class },
+                    x: object.x,
+                    y: object.y,
+                    gid: object.gid,
+                    properties: json_properties(object.properties),
+                }
+            })),
+            "group" => layers.extend(layer.layers.drain(..).rev()),
+            _ => {}
+        }
+    }
+
+    Ok(map)
+}
+
+/// Collects the `tile` properties of JSON tiles
+fn json_tiles(tiles: Vec<JsonTile>) -> BTreeMap<u32, String> {
+    tiles.into_iter()
+        .filter_map(|tile| Some((tile.id, json_properties(tile.properties).remove("tile")?)))
+        .collect()
+}
+
+/// Loads an external `.tsj` or `.tsx` tileset
+fn load_external_tileset(path: &Path) -> Result<BTreeMap<u32, String>, MapParseError> {
+    let contents = read(path)?;
+    if path.extension().is_some_and(|ext| ext == "tsx") {
+        let document = roxmltree::Document::parse(&contents)
+            .map_err(|e| malformed(format!("{}: {}", path.display(), e)))?;
+        Ok(xml_tiles(document.root_element()))
+    } else {
+        let tileset: JsonTileset = serde_json::from_str(&contents)
+            .map_err(|e| malformed(format!("{}: {}", path.display(), e)))?;
+        Ok(json_tiles(tileset.tiles))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+// XML (.tmx / .tsx)
+
+/// ## Returns
+///
+/// The attribute `name` of `node` parsed as `T`
+fn xml_attribute<T: std::str::FromStr>(
+    node: roxmltree::Node,
+    name: &str,
+) -> Result<T, MapParseError> {
+    node.attribute(name).and_then(|value| value.parse().ok()).ok_or_else(|| malformed(format!(
+        "<{}> has a missing or invalid `{}` attribute", node.tag_name().name(), name
+    )))
+}
+
+/// Collects the `<properties>` of an XML element as strings
+fn xml_properties(node: roxmltree::Node) -> BTreeMap<String, String> {
+    node.children()
+        .filter(|child| child.has_tag_name("properties"))
+        .flat_map(|properties| properties.children().filter(|p| p.has_tag_name("property")))
+        .filter_map(|property| {
+            // Multiline string values are stored as text instead of an attribute
+            let value = property.attribute("value").or(property.text()).unwrap_or_default();
+            Some((property.attribute("name")?.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Collects the `tile` properties of the `<tile>`s of an XML tileset
+fn xml_tiles(tileset: roxmltree::Node) -> BTreeMap<u32, String> {
+    tileset.children()
+        .filter(|child| child.has_tag_name("tile"))
+        .filter_map(|tile| {
+            Some((tile.attribute("id")?.parse().ok()?, xml_properties(tile).remove("tile")?))
+        })
+        .collect()
+}
+
+/// Parses a `.tmx` map. External tilesets are looked up relative to `path`
+fn parse_xml_map(contents: &str, path: &Path) -> Result<TiledMap, MapParseError> {
+    let document = roxmltree::Document::parse(contents).map_err(|e| malformed(e.to_string()))?;
+    let root = document.root_element();
+    if root.attribute("infinite") == Some("1") {
+        return Err(malformed("infinite maps are not supported"));
+    }
+
+    let mut map = TiledMap {
+        width: xml_attribute(root, "width")?,
+        tile_width: xml_attribute(root, "tilewidth")?,
+        tile_height: xml_attribute(root, "tileheight")?,
+        ..Default::default()
+    };
+
+    // Layer groups are walked in document order, so their layers keep their drawing order
+    for node in root.descendants() {
+        match node.tag_name().name() {
+            "tileset" if node.parent() == Some(root) => {
+                let tiles = match node.attribute("source") {
+                    Some(source) => load_external_tileset(&path.with_file_name(source))?,
+                    None => xml_tiles(node),
+                };
+                let first_gid = xml_attribute(node, "firstgid")?;
+                map.tilesets.push(TiledTileset { first_gid, tiles });
+            }
+            "layer" => {
+                let name = node.attribute("name").unwrap_or_default().to_string();
+                let data = node.children().find(|child| child.has_tag_name("data"));
+                let gids = match data.map(|data| (data, data.attribute("encoding"))) {
+                    Some((data, Some("csv"))) => data.text().unwrap_or_default()
+                        .split(',')
+                        .map(|gid| gid.trim().parse().ok())
+                        .collect::<Option<Vec<u32>>>(),
+                    Some((data, None)) => data.children()
+                        .filter(|child| child.has_tag_name("tile"))
+                        .map(|tile| tile.attribute("gid").unwrap_or("0").parse().ok())
+                        .collect(),
+                    _ => None,
+                };
+                let gids = gids.ok_or_else(|| malformed(format!(
+                    "layer `{}` must be stored as CSV, not compressed", name
+                )))?;
+                map.layers.push(TiledLayer { name, gids });
+            }
+            "object" => map.objects.push(TiledObject {
+                id: xml_attribute(node, "id")?,
+                name: node.attribute("name").unwrap_or_default().to_string(),
+                class: node.attribute("class").or(node.attribute("type"))
+                    .unwrap_or_default()
+                    .to_string(),
+                x: xml_attribute(node, "x")?,
+                y: xml_attribute(node, "y")?,
+                gid: node.attribute("gid").and_then(|gid| gid.parse().ok()),
+                properties: xml_properties(node),
+            }),
+            _ => {}
+        }
+    }
+
+    Ok(map)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::npc::Behaviour;
+    use crate::world::WorldMap;
+
+    /// The ASCII map both Tiled fixtures describe
+    const EXPECTED_MAP: &[u8] = b"\
+#######
+#.K...#
+#...D.#
+###|###
+#.....#
+#######
+~[keys]
+~K(1,2) = D(3,3)
+~[treasure]
+~T(2,4) = (1 = 250)
+~[npcs]
+~N(4,2) = Goblin(g) PATROL (4,5) STATS(8,4,1)
+~[spawns]
+~S(1,1) = start
+";
+
+    /// Imports a fixture and checks it against [EXPECTED_MAP]
+    fn assert_imports(path: &str) {
+        let registries = Registries::default();
+        let document = load_tiled_map(path, &registries).unwrap();
+
+        // The tile layer inside the group is drawn over the base layer
+        let layers: Vec<&str> = document.layers.iter().map(|layer| layer.name.as_str()).collect();
+        assert_eq!(layers, ["terrain", "items"]);
+        assert_eq!(document.layers[0].rows[1], "#.....#");
+        assert_eq!(document.layers[1].rows[1], "  K    ");
+
+        assert_eq!(document.keys, [KeyEntry { key: (1, 2), door: (3, 3) }]);
+        assert_eq!(document.chests, [ChestEntry { position: (2, 4), items: vec![(1, 250)] }]);
+        // A tile object is anchored at its bottom-left corner
+        assert_eq!(document.spawns.get("start"), Some(&(1, 1)));
+        assert_eq!(document.npcs.len(), 1);
+        assert_eq!((document.npcs[0].position, document.npcs[0].glyph), ((4, 2), 'g'));
+
+        let expected = WorldMap::from_bytes(EXPECTED_MAP, 0, &registries).unwrap();
+        let (map, _) = WorldMap::parse_document(document.to_ron().as_bytes(), 0, &registries)
+            .unwrap();
+        assert_eq!(map, expected);
+        assert!(matches!(map.npcs[0].behaviour, Behaviour::Patrol { .. }));
+    }
+
+    #[test]
+    fn imports_json_maps() {
+        assert_imports("assets/test_tiled.tmj");
+    }
+
+    #[test]
+    fn imports_xml_maps() {
+        assert_imports("assets/test_tiled.tmx");
+    }
+
+    #[test]
+    fn missing_property_is_reported() {
+        let path = Path::new("assets/test_tiled.tmj");
+        let contents = std::fs::read_to_string(path).unwrap()
+            .replace(r#"{ "name": "items", "type": "string", "value": "1 = 250" }"#, "");
+
+        let result = parse_json_map(&contents, path).unwrap()
+            .into_document("test_tiled", &Registries::default());
+
+        assert_eq!(result, Err(MapParseError::single(MapParseErrorKind::MalformedDocument {
+            reason: "chest object 3 is missing the `items` property".to_string()
+        })));
+    }
+}
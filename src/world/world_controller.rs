@@ -1,34 +1,54 @@
 //! # World Controller
 //!
-//! Manages the game world state, interactions, and generating renderables to pass to the 
+//! Manages the game world state and interactions which are rendered by the
 //! [WorldView](crate::world::WorldView)
 //!
 //! ## Overview
 //! The WorldController handles:
 //! - Map generation (but *not* rendering - this is handled by the WorldView)
-//! - Processing world update events
-//! - Managing interactions between player and world elements (doors, keys, treasure, etc.)
+//! - Processing world update events, and reporting their outcomes to the registered
+//!   [WorldListener]s
+//! - Advancing the world clock one simulation tick at a time (see [WorldController::tick])
+//! - Remembering the tiles the player has seen on each map
+//! - Managing interactions between player and world elements (doors, keys, treasure, levers and
+//!   pressure plates, etc.)
 //!
 //! #### Version: 0.0.1
 //!
 //! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
 
 // > USE
-use std::collections::{BinaryHeap};
-use std::io::Read;
-use std::path::Path;
-use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Widget};
+use std::collections::binary_heap::{BinaryHeap, PeekMut};
 
 // > CRATE
-use crate::shared::constants::TILE_WIDTH;
 use crate::shared::traits::{
     Moveable, Positionable, ToIdentifiableChar
 };
-use crate::shared::CommonState;
+use crate::shared::constants::{FOV_RADIUS, NPC_TURN_TICKS};
+use crate::shared::{hit_damage, CommonState, MapEntry, Tile, WorldCoordinates};
 use crate::shared::treasure::*;
 use crate::Player;
-use crate::world::{WorldMap, WorldUpdate, WorldUpdateEventType};
+use crate::registry::{Registries, TileInteraction};
+use crate::world::{
+    self as world, Combatant, FieldOfView, ListenerId, MapDocument, MapParseError,
+    MapParseErrorKind, WorldEvent, WorldListener, WorldMap, WorldUpdate, WorldUpdateEventType,
+    read_map_source,
+};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` deciding when NPCs take their turns
+///
+/// * `TurnBased` - Every NPC takes a turn right after each player action which
+///   [takes a turn](crate::game::GameAction::takes_turn); the world waits for the player
+/// * `RealTime` - Every NPC takes a turn every few [ticks](WorldController::tick), whether the
+///   player acts or not
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeMode {
+    #[default]
+    TurnBased,
+    RealTime,
+}
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -36,220 +56,955 @@ use crate::world::{WorldMap, WorldUpdate, WorldUpdateEventType};
 ///
 /// Controls the map state and handles queued world update events,
 /// such as key pickups and door interactions.
-pub struct WorldController<'wctrl> {
+pub struct WorldController {
     pub maps: Vec<WorldMap>,
-    pub update_queue: &'wctrl mut BinaryHeap<WorldUpdate<WorldUpdateEventType>>,
+    pub active_map_id: usize,
+    pub update_queue: BinaryHeap<WorldUpdate<WorldUpdateEventType>>,
+    pub registries: Registries,
+    /// The number of simulation ticks which have passed since the world was created
+    pub ticks: u64,
+    pub time_mode: TimeMode,
+    /// The sequence number given to the next queued update
+    pub next_update_sequence: u64,
+    listeners: Vec<(ListenerId, WorldListener)>,
+    next_listener_id: u64,
 }
 
-impl<'wctrl> WorldController<'wctrl> {
-    /// Creates a new WorldController with the default map
+// IMPL Default
+impl Default for WorldController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldController {
+    /// Creates a new WorldController with no maps loaded, an empty [WorldUpdate] queue and the
+    /// built-in definitions
+    pub fn new() -> Self {
+        Self::with_registries(Registries::default())
+    }
+
+    /// Creates a new WorldController with no maps loaded and an empty [WorldUpdate] queue, using
+    /// the given definitions
     ///
     /// # Arguments
-    /// * `update_queue` ([BinaryHeap]) - Handles [WorldUpdate] requests 
-    pub fn new(update_queue: &'wctrl mut BinaryHeap<WorldUpdate<WorldUpdateEventType>>) -> Self {
+    /// * `registries` (`Registries`) - The definitions of every tile and item maps may use
+    pub fn with_registries(registries: Registries) -> Self {
         Self {
             maps: vec![],
-            update_queue
+            active_map_id: 0,
+            update_queue: BinaryHeap::new(),
+            registries,
+            ticks: 0,
+            time_mode: TimeMode::default(),
+            next_update_sequence: 0,
+            listeners: vec![],
+            next_listener_id: 0,
         }
     }
 
-    pub fn load_map_from_fstr(&mut self, map_file: &str) {
-        let mut buf: Vec<u8> = vec![];
-        std::fs::File::open_buffered(Path::new(map_file))
-                                .unwrap_or_else(|_| panic!(
-                                    "Unable to open map file: {}.", map_file
-                                ))
-                                .read_to_end(&mut buf)
-                                .expect("Unable to read contents of test file.");
+    /// Loads a [WorldMap] from the map file at the given path and appends it to the loaded maps
+    ///
+    /// # Arguments
+    /// * `map_file` (`&str`) - The path to the txt WorldMap file, a `.ron` [MapDocument] or a
+    ///   Tiled map (see [read_map_source])
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] if the file cannot be read, or containing ***every*** problem
+    /// found while parsing it, including `~M` links between it and the loaded maps which enter
+    /// either map where the player cannot stand.
+    pub fn load_map_from_fstr(&mut self, map_file: &str) -> Result<(), MapParseError> {
+        let source = read_map_source(map_file, &self.registries)?;
         let assigned_id = self.next_map_id();
+        let map = WorldMap::from_bytes(source.as_bytes(), assigned_id, &self.registries)?;
+
+        let errors = self.invalid_map_entries(&map);
+        if !errors.is_empty() {
+            return Err(MapParseError { errors, warnings: vec![] });
+        }
+
+        self.maps.push(map);
+        Ok(())
+    }
 
-        self.maps.push(WorldMap::from_bytes(&buf, assigned_id));
+    /// Checks the coordinates entered by every `~M` link between the `map` and the loaded maps
+    /// ( or the `map` itself ). Links to maps which haven't been loaded yet are checked once they
+    /// are.
+    ///
+    /// ## Returns
+    ///
+    /// An [InvalidMapEntry](MapParseErrorKind::InvalidMapEntry) for every link entering its
+    /// target map outside of the grid or on a tile the player cannot stand on
+    fn invalid_map_entries(&self, map: &WorldMap) -> Vec<MapParseErrorKind> {
+        self.maps.iter()
+            .chain([map])
+            .flat_map(|from| from.grid.indexed_iter().filter_map(move |(door, tile)| match tile {
+                Tile::Door(props, _) => props.dml.as_ref().map(|dml| (from.id, door, dml)),
+                _ => None,
+            }))
+            .filter(|(map_id, _, dml)| *map_id == map.id || dml.map_id == map.id)
+            .filter_map(|(map_id, door, dml)| {
+                let MapEntry::Coords(entry) = dml.entry else {
+                    return None;
+                };
+                let target = match dml.map_id == map.id {
+                    true => map,
+                    false => self.map(dml.map_id)?,
+                };
+                (!target.is_passable(entry)).then_some(MapParseErrorKind::InvalidMapEntry {
+                    map_id, door, target: dml.map_id, entry
+                })
+            })
+            .collect()
     }
 
-    /// Handles updates pushed to the update event queue
+    /// Adds a world update to the queue for later processing. Updates are processed once their
+    /// due tick has been reached, in the order given by [WorldUpdate]'s `Ord`; updates with the
+    /// same due tick and priority are processed in the order they were queued. An update without
+    /// a map id applies to the map active now.
     ///
     /// # Arguments
-    /// * `&player` - ([`Player`]) An ***immutable*** reference to the player object.
+    /// * `update<T: WorldUpdateEventType>` - ([`WorldUpdate`]) The world update event to queue
     /// 
-    pub fn generate_map(&'wctrl self, player: &'wctrl Player) -> impl Widget + 'wctrl {
-        /// ## MapWidget
-        /// 
-        /// Widget for rendering the world map with the player character
-        struct MapWidget<'wctrl> {
-            controller: &'wctrl WorldController<'wctrl>,
-            player: &'wctrl Player,
-        }
-
-        // IMPL MapWidget
-        impl<'wctrl> Widget for MapWidget<'wctrl> {
-            fn render(self, area: Rect, buf: &mut Buffer) {
-                let map_width = self.controller.maps[0].grid.cols();
-                let map_height = self.controller.maps[0].grid.rows();
-
-                let world_width = map_width as u16 + 2;
-                let world_height = map_height as u16 + 2;
-
-                let map_area = centered_rect(world_width, world_height, area);
+    pub fn queue_update(&mut self, mut update: WorldUpdate<WorldUpdateEventType>) {
+        update.map_id.get_or_insert(self.active_map_id);
+        update.sequence = self.next_update_sequence;
+        self.next_update_sequence += 1;
+        self.update_queue.push(update);
+    }
 
-                let block = Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::White));
-                
-                let inner = block.inner(map_area);
+    /// Queues a world update to be processed a number of ticks from now
+    ///
+    /// # Arguments
+    /// * `update<T: WorldUpdateEventType>` - ([`WorldUpdate`]) The world update event to queue
+    /// * `delay` - (`u64`) How many ticks to wait before processing the update
+    ///
+    pub fn schedule_update(&mut self, mut update: WorldUpdate<WorldUpdateEventType>, delay: u64) {
+        update.due_tick = self.ticks + delay;
+        self.queue_update(update);
+    }
 
-                for row in 0..map_height {
-                    for col in 0..(map_width / TILE_WIDTH as usize) {
-                        if  row >= self.controller.maps[0].grid.rows() 
-                            || col >= self.controller.maps[0].grid.cols() 
-                        {
-                            continue;
-                        }
+    /// Registers a listener which is run for every [WorldEvent] from now on, in the order the
+    /// listeners were subscribed
+    ///
+    /// # Arguments
+    /// * `listener` - (`impl FnMut(&WorldEvent, &mut WorldController, &Player)`) The callback
+    ///   (see [WorldListener])
+    ///
+    /// # Returns
+    /// The id to [unsubscribe](WorldController::unsubscribe) the listener with
+    pub fn subscribe(
+        &mut self,
+        listener: impl FnMut(&WorldEvent, &mut WorldController, &Player) + 'static,
+    ) -> ListenerId {
+        let id = ListenerId(self.next_listener_id);
+        self.next_listener_id += 1;
+        self.listeners.push((id, Box::new(listener)));
+        id
+    }
 
-                        let x = inner.x + (col as u16 * TILE_WIDTH);
-                        let y = inner.y + row as u16;
+    /// Removes a listener registered with [WorldController::subscribe]
+    ///
+    /// # Arguments
+    /// * `id` - (`ListenerId`) The id returned when the listener was subscribed
+    ///
+    /// # Returns
+    /// `false` if no such listener is registered. Listeners cannot be removed while an event is
+    /// being reported to them
+    pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+        let count = self.listeners.len();
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+        self.listeners.len() != count
+    }
 
-                        let tile = &self.controller.maps[0].grid[(row, col)];
-                        let c = tile.to_char_id();
-                        let style = Style::default().bg(Color::Black);
+    /// Reports an event to every listener
+    fn emit(&mut self, event: WorldEvent, player: &Player) {
+        // Take the listeners out so each one can be handed the controller
+        let mut listeners = std::mem::take(&mut self.listeners);
+        for (_, listener) in &mut listeners {
+            listener(&event, self, player);
+        }
 
-                        // Make sure we're within bounds
-                        if x < inner.x + inner.width && y < inner.y + inner.height {
-                            if row == self.player.row() && col == self.player.col() {
-                                buf[(x, y)]
-                                    .set_symbol(&format!("{:2}", self.player.to_char_id()))
-                                    .set_style(style);
-                            }
-                            // Draw the tile
-                            else {
-                                buf[(x, y)].set_symbol(&format!("{:2}", c)).set_style(style);
-                            }
-                        }
-                    }
-                }
+        // Keep any listeners subscribed while the event was being reported
+        listeners.append(&mut self.listeners);
+        self.listeners = listeners;
+    }
 
-                block.render(map_area, buf);
+    /// Queues the state changes of flipping a lever or pressure plate: the switch itself changes
+    /// to `ACTIVE` or `INACTIVE`. A lever toggles every tile linked to it, from whatever state the
+    /// tile is in when the change is processed. A plate holds its doors `UNLOCKED` ( and its
+    /// levers `ACTIVE` ) while pressed and `LOCKED` ( `INACTIVE` ) while released, replacing any
+    /// change still pending for them.
+    ///
+    /// # Arguments
+    /// * `coords` - (`WorldCoordinates`) The switch on the active map
+    /// * `active` - (`bool`) Whether the switch is turned on or off
+    ///
+    pub fn flip_switch(&mut self, coords: WorldCoordinates, active: bool) {
+        let Some(switch) = self.active_map().grid.get(coords.0, coords.1) else {
+            return;
+        };
+
+        let plate = matches!(switch, Tile::Plate(_, _));
+        let state = if active { CommonState::ACTIVE } else { CommonState::INACTIVE };
+        let mut changes = vec![WorldUpdateEventType::ChangeTileState(coords, state)];
+        for target in switch.get_properties().switch_targets.clone() {
+            if !plate {
+                changes.push(WorldUpdateEventType::ToggleTileState(target));
+                continue;
             }
-        }
 
-        // Helper function to create a centered rect
-        fn centered_rect(width: u16, height: u16, r: Rect) -> Rect {
-            let x = r.x + (r.width.saturating_sub(width)) / 2;
-            let y = r.y + (r.height.saturating_sub(height)) / 2;
-            
-            Rect::new(
-                x,
-                y,
-                width.min(r.width),
-                height.min(r.height)
-            )
+            let target_state = match (&self.active_map().grid[target], active) {
+                (Tile::Door(_, _), true) => CommonState::UNLOCKED,
+                (Tile::Door(_, _), false) => CommonState::LOCKED,
+                (_, true) => CommonState::ACTIVE,
+                (_, false) => CommonState::INACTIVE,
+            };
+            let map_id = Some(self.active_map_id);
+            self.update_queue.retain(|update| update.map_id != map_id || !matches!(
+                update.event_type,
+                WorldUpdateEventType::ChangeTileState(pending, _) if pending == target
+            ));
+            changes.push(WorldUpdateEventType::ChangeTileState(target, target_state));
         }
 
-        // Return the MapWidget
-        MapWidget {
-            controller: self,
-            player,
+        for change in changes {
+            self.queue_update(WorldUpdate::new(change));
         }
     }
 
-    /// Adds a world update to the queue for later processing
-    ///
-    /// # Arguments
-    /// * `update<T: WorldUpdateEventType>` - ([`WorldUpdate`]) The world update event to queue
-    /// 
-    pub fn queue_update(&mut self, update: WorldUpdate<WorldUpdateEventType>) {
-        self.update_queue.push(update);
+    /// Flips every pressure plate of the active map whose state doesn't match whether the player
+    /// or an NPC is standing on it
+    fn press_plates(&mut self, player: &Player) {
+        let map = self.active_map();
+        let flipped: Vec<(WorldCoordinates, bool)> = map.grid.indexed_iter()
+            .filter_map(|(coords, tile)| match tile {
+                Tile::Plate(_, state) => {
+                    let occupied = (player.row(), player.col()) == coords
+                        || map.npc_at(coords).is_some();
+                    (occupied != (*state == CommonState::ACTIVE)).then_some((coords, occupied))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for (coords, occupied) in flipped {
+            self.flip_switch(coords, occupied);
+        }
     }
 
-    /// Handles updates pushed to the update event queue
+    /// Handles the updates in the update event queue which are due on the current tick, leaving
+    /// the rest queued. Each update applies to the map it was queued for, whichever map is active;
+    /// updates for maps which aren't loaded are dropped. Pressure plates which have been stepped
+    /// on or off since the last update are flipped first. The outcome of each update is reported
+    /// to the listeners as a [WorldEvent] once it has been applied.
     ///
     /// # Arguments
     /// * `&mut player` - ([`Player`]) A ***mutable*** reference to the player
     /// 
     pub fn update_world(&mut self, player: &mut Player) {
-        while let Some(update) = self.update_queue.pop() {
+        if !self.maps.is_empty() {
+            self.press_plates(player);
+        }
+
+        while let Some(update) = self.pop_due_update() {
+            let mut events: Vec<WorldEvent> = vec![];
+            let map_id = update.map_id.unwrap_or(self.active_map_id);
+            // The player only takes part in updates on the map they're on
+            let on_active_map = map_id == self.active_map_id;
+
             match update.event_type {
                 // IDLE EVENT
                 // -> Nothing Happens
                 WorldUpdateEventType::Idle => {},
 
-                // CHANGE TILE STATE
-                // -> TODO: Not yet implemented.
+                // CHANGE / TOGGLE TILE STATE
+                // -> Changes the state of a tile on the update's map, restyling it after the tile
+                // definition of its new state. A door which something stands in isn't locked
+                // yet; the update is retried on the next tick instead
                 // NOTE: used for changing tile states outside of interaction, such as pressing a 
                 // button to open a door. Interaction should be handled within a separate event 
                 // state if there's translation or state transfer involved (see TryOpenDoor)
-                WorldUpdateEventType::ChangeTileState(_, _) => { todo!() },
+                WorldUpdateEventType::ChangeTileState(coords, _)
+                | WorldUpdateEventType::ToggleTileState(coords) => {
+                    let Some(map) = self.map(map_id) else {
+                        continue;
+                    };
+                    let Some(tile) = map.grid.get(coords.0, coords.1) else {
+                        continue;
+                    };
+                    let state = match update.event_type {
+                        WorldUpdateEventType::ChangeTileState(_, state) => state,
+                        _ => match tile.get_state() {
+                            Some(state) => state.toggled(),
+                            None => continue,
+                        },
+                    };
+
+                    let occupied = (on_active_map && (player.row(), player.col()) == coords)
+                        || map.npc_at(coords).is_some();
+                    let door = matches!(tile, Tile::Door(_, _));
+                    if door && state == CommonState::LOCKED && occupied {
+                        self.schedule_update(update, 1);
+                        continue;
+                    }
+
+                    if tile.get_state() != Some(&state) {
+                        events.extend(match tile {
+                            Tile::Door(_, _) if state == CommonState::UNLOCKED => {
+                                Some(WorldEvent::DoorOpened(coords))
+                            }
+                            Tile::Door(_, _) => Some(WorldEvent::DoorClosed(coords)),
+                            Tile::Lever(_, _) | Tile::Plate(_, _) => {
+                                Some(WorldEvent::SwitchFlipped {
+                                    switch: coords, active: state == CommonState::ACTIVE
+                                })
+                            }
+                            _ => None,
+                        });
+                    }
+
+                    let restyle = self.registries.tiles
+                        .for_state(TileInteraction::of(tile), state)
+                        .cloned();
+                    let Some(map) = self.map_mut(map_id) else {
+                        continue;
+                    };
+                    let t_mut = &mut map.grid[coords];
+                    if let Some(def) = restyle {
+                        def.apply_to(t_mut.get_properties_mut());
+                    }
+                    t_mut.change_state(state);
+                },
 
                 // KEY PICKUP
+                // -> Moves the key onto the player's keyring, leaving the tile looking like the
+                // key definition's undiscovered display. Anything but a key tile is ignored
                 WorldUpdateEventType::KeyPickup(kdl) => { 
                     let (kr, kc) = (kdl.key_coords.0, kdl.key_coords.1);
-
+                    let picked_up = self.registries.tiles
+                        .for_interaction(TileInteraction::Key)
+                        .cloned()
+                        .expect("Key tiles are only created from a key definition.");
+
+                    let Some(map) = self.map_mut(map_id) else {
+                        continue;
+                    };
                     // We can get away with just getting a reference to the TileProperties
-                    let tp_mut = &mut self.maps[0].grid[(kr, kc)].get_properties_mut();
+                    let Some(Tile::Key(tp_mut)) = map.grid.get_mut(kr, kc) else {
+                        continue;
+                    };
 
                     player.keyring.push(kdl);
                     tp_mut.kdl = None;
-                    tp_mut.draw_character = '.';
+                    picked_up.apply_to(tp_mut);
+                    events.push(WorldEvent::KeyPickedUp(kdl));
                  },
 
                  // DOOR INTERACTION
-                 // -> Does not open without the appropriate KeyDoorLink on the player's keyring
+                 // -> Does not open without the appropriate KeyDoorLink on the player's keyring.
+                 // Anything but a door tile is ignored
                  WorldUpdateEventType::TryOpenDoor(door_coords) => {
+                    let (dr, dc) = (door_coords.0, door_coords.1);
+                    let open_door = self.registries.tiles
+                        .for_state(TileInteraction::Door, CommonState::UNLOCKED)
+                        .cloned()
+                        .expect("The tile registry always defines an unlocked door.");
+
+                    let Some(t_mut) = self.map_mut(map_id)
+                        .and_then(|map| map.grid.get_mut(dr, dc))
+                        .filter(|tile| matches!(tile, Tile::Door(_, _)))
+                    else {
+                        continue;
+                    };
+
                     // Find the matching KeyDoorLink that opens this door
                     if let Some(kdl) = player.keyring.pop_if(|kdl| kdl.door_coords == door_coords) {
-                        let dr = door_coords.0;
-                        let dc = door_coords.1;
-                        let t_mut = &mut self.maps[0].grid[(dr, dc)];
                         t_mut.get_properties_mut().kdl = None;
-                        t_mut.get_properties_mut().draw_character = '\\';
+                        open_door.apply_to(t_mut.get_properties_mut());
                         t_mut.change_state(CommonState::UNLOCKED);
-                        player.translate_into();
+                        if on_active_map {
+                            player.translate_into();
+                        }
+                        events.push(WorldEvent::DoorOpened(door_coords));
+                    } else {
+                        events.push(WorldEvent::DoorLocked(door_coords));
+                    }
+                }
+
+                // MAP TRANSITION
+                // -> Activates the linked map and places the player on its entry point. Links to
+                // maps which haven't been loaded, or to spawn points they don't declare, are
+                // ignored
+                WorldUpdateEventType::MapTransition(dml) => {
+                    if let Some(entry) = self.maps.iter()
+                        .find(|map| map.id == dml.map_id)
+                        .and_then(|map| map.entry_point(&dml.entry))
+                    {
+                        self.active_map_id = dml.map_id;
+                        player.set_position(entry);
+                        events.push(WorldEvent::MapEntered { map_id: dml.map_id, entry });
+                    }
+                }
+
+                // ATTACK
+                // -> Deals one hit of damage from the attacker to the defender, queueing the
+                // defender's death once its hit points run out. Combatants which have already
+                // died or moved away ( including the player leaving the map ) are ignored
+                WorldUpdateEventType::Attack { attacker, defender } => {
+                    let Some(map) = self.map(map_id).filter(|_| on_active_map) else {
+                        continue;
+                    };
+                    let items = &self.registries.items;
+                    let attack = match attacker {
+                        Combatant::Player if !player.stats.is_dead() => player.attack(items),
+                        Combatant::Npc(coords) => match map.npc_at(coords) {
+                            Some(npc) if !npc.stats.is_dead() => npc.stats.attack,
+                            _ => continue,
+                        },
+                        _ => continue,
+                    };
+
+                    let (stats, damage) = match defender {
+                        Combatant::Player => {
+                            let damage = hit_damage(attack, player.defense(items));
+                            player.stats.take_damage(damage);
+                            (&player.stats, damage)
+                        }
+                        Combatant::Npc(coords) => {
+                            let Some(npc) = self.map_mut(map_id)
+                                .and_then(|map| map.npc_at_mut(coords))
+                            else {
+                                continue;
+                            };
+                            let damage = hit_damage(attack, npc.stats.defense);
+                            npc.stats.take_damage(damage);
+                            (&npc.stats, damage)
+                        }
+                    };
+
+                    if stats.is_dead() {
+                        let death = WorldUpdateEventType::Death(defender);
+                        self.queue_update(WorldUpdate::new(death).on_map(map_id));
                     }
+                    events.push(WorldEvent::Damaged { attacker, defender, damage });
                 }
 
+                // DEATH
+                // -> Removes a dead NPC from the map and rewards the player with its experience.
+                // The player's own death ends the game (see Game::apply)
+                WorldUpdateEventType::Death(combatant) => match combatant {
+                    Combatant::Npc(coords) => {
+                        let Some(map) = self.map_mut(map_id) else {
+                            continue;
+                        };
+                        let npcs = &mut map.npcs;
+                        if let Some(idx) = npcs.iter().position(|npc| {
+                            (npc.row(), npc.col()) == coords && npc.stats.is_dead()
+                        }) {
+                            let npc = npcs.remove(idx);
+                            let xp = npc.xp_reward();
+                            player.stats.gain_xp(xp);
+                            events.push(WorldEvent::NpcKilled { coords, name: npc.name, xp });
+                        }
+                    }
+                    Combatant::Player => events.push(WorldEvent::PlayerDied),
+                },
+
                 // TREAURE CHEST INTERACTION
+                // -> Loots an uncollected chest. Anything but an uncollected chest is ignored
                 WorldUpdateEventType::PickupTreasure(tcoords) => {
                     let tr = tcoords.0;
                     let tc = tcoords.1;
 
+                    let empty_chest = self.registries.tiles
+                        .for_state(TileInteraction::Treasure, CommonState::COLLECTED)
+                        .cloned()
+                        .expect("The tile registry always defines a collected treasure.");
+
                     // Refer to the whole tile to allow state change
-                    let t_mut = &mut self.maps[0].grid[(tr, tc)];
-
-                    if t_mut.get_state().unwrap() == &CommonState::UNCOLLECTED {
-                        let tcoll = t_mut.get_properties_mut().treasure.as_ref().unwrap();
-
-                        tcoll.items.iter().for_each(|(t, q)| {
-                            match *t {
-                                TreasureType::Gold => { player.add_gold(*q) }
-                                // TODO: Implement Armor, Weapons, and Potion Treasures
-                                TreasureType::Armor => { todo!() },
-                                TreasureType::Potion => { todo!() },
-                                TreasureType::Weapon => { todo!() }
-                            }
+                    let Some(t_mut) = self.map_mut(map_id)
+                        .and_then(|map| map.grid.get_mut(tr, tc))
+                        .filter(|tile| matches!(tile, Tile::Treasure(_, CommonState::UNCOLLECTED)))
+                    else {
+                        continue;
+                    };
+
+                    let tcoll = t_mut.get_properties_mut().treasure.take();
+                    empty_chest.apply_to(t_mut.get_properties_mut());
+                    t_mut.change_state(CommonState::COLLECTED);
+
+                    if let Some(tcoll) = tcoll {
+                        for (t, q) in &tcoll.items {
+                            player.add_item(*t, *q, &self.registries.items);
+                        }
+                        events.push(WorldEvent::TreasureCollected {
+                            chest: tcoords, items: tcoll.items
                         });
                     }
-                    
-                    t_mut.get_properties_mut().treasure = None;
-                    t_mut.get_properties_mut().draw_character = '0';
-                    t_mut.change_state(CommonState::COLLECTED);
                 }
             }
+
+            for event in events {
+                self.emit(event, player);
+            }
         }
+
+        self.explore_around(player);
     }
 
-    /// Checks if a given row/col pair is within the map bounds. Row/col pair is passed as isize
-    /// to check for negative bounds (this shouldn't realistically happen, but just in case)
+    /// Removes the next update to process from the update event queue, if it is due
+    fn pop_due_update(&mut self) -> Option<WorldUpdate<WorldUpdateEventType>> {
+        let next = self.update_queue.peek_mut()?;
+        (next.due_tick <= self.ticks).then(|| PeekMut::pop(next))
+    }
+
+    /// Advances the world by one simulation tick. In [TimeMode::RealTime] every NPC takes a turn
+    /// once every `NPC_TURN_TICKS` ticks; any resulting updates are processed immediately.
     ///
     /// # Arguments
     /// * `&mut player` - ([`Player`]) A ***mutable*** reference to the player
+    ///
+    pub fn tick(&mut self, player: &mut Player) {
+        self.ticks += 1;
+
+        if self.time_mode == TimeMode::RealTime && self.ticks.is_multiple_of(NPC_TURN_TICKS) {
+            world::npc_turns(self, player);
+        }
+        self.update_world(player);
+    }
+
+    /// Makes a loaded map active and places the player on one of its spawn points
+    ///
+    /// # Arguments
+    /// * `player` - (`&mut Player`) The player to place
+    /// * `map_id` - (`usize`) The id of the map to spawn on
+    /// * `spawn` - (`&str`) The name of the spawn point, declared with a `~S` directive
+    ///
+    /// # Returns
+    /// `false` (changing nothing) if the map isn't loaded or doesn't declare the spawn point
+    pub fn spawn_player(&mut self, player: &mut Player, map_id: usize, spawn: &str) -> bool {
+        let Some(coords) = self.maps.iter()
+            .find(|map| map.id == map_id)
+            .and_then(|map| map.spawn(spawn))
+        else {
+            return false;
+        };
+
+        self.active_map_id = map_id;
+        player.set_position(coords);
+        self.explore_around(player);
+        self.emit(WorldEvent::MapEntered { map_id, entry: coords }, player);
+        true
+    }
+
+    /// Computes the field of view of a `Positionable` on the active map
+    ///
+    /// # Arguments
+    /// * `viewer` - (`&impl Positionable`) The object looking around (usually the player)
+    ///
+    pub fn field_of_view(&self, viewer: &impl Positionable) -> FieldOfView {
+        FieldOfView::compute(self.active_map(), (viewer.row(), viewer.col()), FOV_RADIUS)
+    }
+
+    /// Remembers every tile of the active map in the field of view of a `Positionable`
+    ///
+    /// # Arguments
+    /// * `viewer` - (`&impl Positionable`) The object looking around (usually the player)
+    ///
+    pub fn explore_around(&mut self, viewer: &impl Positionable) {
+        let fov = self.field_of_view(viewer);
+        self.active_map_mut().explore(&fov);
+    }
+
+    /// Checks if a given row/col pair is within the active map bounds. Row/col pair is passed as
+    /// isize to check for negative bounds (this shouldn't realistically happen, but just in case)
+    ///
+    /// # Arguments
+    /// * `row` - (`&isize`) The row ( y ) coordinate to check
+    /// * `col` - (`&isize`) The column ( x ) coordinate to check
     /// 
     pub fn within_bounds(&self, row: &isize, col: &isize) -> bool {
         (
-            row >= &0 && row <= &(self.maps[0].grid.rows() as isize)) 
-            && (col >= &0 && col <= &(self.maps[0].grid.cols() as isize)
+            row >= &0 && row < &(self.active_map().grid.rows() as isize)) 
+            && (col >= &0 && col < &(self.active_map().grid.cols() as isize)
         )
     }
 
+    /// ## Returns
+    ///
+    /// An ***immutable*** reference to the [WorldMap] the player is currently on
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no map with the `active_map_id` has been loaded
+    pub fn active_map(&self) -> &WorldMap {
+        self.maps.iter()
+            .find(|map| map.id == self.active_map_id)
+            .expect("No map has been loaded for the active map id.")
+    }
+
+    /// ## Returns
+    ///
+    /// A ***mutable*** reference to the [WorldMap] the player is currently on
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no map with the `active_map_id` has been loaded
+    pub fn active_map_mut(&mut self) -> &mut WorldMap {
+        let id = self.active_map_id;
+        self.maps.iter_mut()
+            .find(|map| map.id == id)
+            .expect("No map has been loaded for the active map id.")
+    }
+
+    /// ## Returns
+    ///
+    /// An ***immutable*** reference to the loaded [WorldMap] with the given id, if any
+    pub fn map(&self, id: usize) -> Option<&WorldMap> {
+        self.maps.iter().find(|map| map.id == id)
+    }
+
+    /// ## Returns
+    ///
+    /// A ***mutable*** reference to the loaded [WorldMap] with the given id, if any
+    pub fn map_mut(&mut self, id: usize) -> Option<&mut WorldMap> {
+        self.maps.iter_mut().find(|map| map.id == id)
+    }
+
+    /// Returns the id that will be assigned to the next loaded map (maps are numbered in the
+    /// order they are loaded, starting at 0)
     pub fn next_map_id(&self) -> usize {
-        if self.maps.is_empty() { self.maps.len() } else { self.maps.len() + 1 }
+        self.maps.len()
+    }
+}
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::npc::{Behaviour, Npc};
+    use crate::shared::extlib::NAVector3;
+    use crate::shared::{KeyDoorLink, MoveDirection, Stats};
+    use crate::world;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A lever at (1,2) and a pressure plate at (3,2), each opening the door two tiles right
+    const SWITCH_MAP: &[u8] = b"\
+#######
+#./.|.#
+#.....#
+#._.|.#
+#######
+~[switches]
+~W(1,2) = (1,4)
+~W(3,2) = (3,4)
+";
+
+    /// Creates a world on `test_map1.txt` with the player next to the chest at (21,1)
+    fn new_world() -> (WorldController, Player) {
+        let mut world = WorldController::new();
+        world.load_map_from_fstr("assets/test_map1.txt").unwrap();
+        (world, Player::new('@', NAVector3::new(1, 22, 1)))
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn map_links_must_enter_where_the_player_can_stand() {
+        let mut world = WorldController::new();
+        let dir = std::env::temp_dir()
+            .join(format!("ferrisyum-map-links-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (first, second) = (dir.join("first.txt"), dir.join("second.txt"));
+
+        // Each map's link enters the other on a wall or outside of its grid
+        std::fs::write(&first, "#####\n#..\\#\n#####\n~M(1,3) = RIGHT -> 1(0,1)\n").unwrap();
+        std::fs::write(&second, "####\n\\..#\n####\n~M(1,0) = LEFT -> 0(9,9)\n").unwrap();
+
+        // The first map's link is only checked once the map it leads to is loaded
+        world.load_map_from_fstr(first.to_str().unwrap()).unwrap();
+        let result = world.load_map_from_fstr(second.to_str().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let invalid = |map_id, door, target, entry| {
+            MapParseErrorKind::InvalidMapEntry { map_id, door, target, entry }
+        };
+        assert_eq!(result.unwrap_err().errors, vec![
+            invalid(0, (1, 3), 1, (0, 1)),
+            invalid(1, (1, 0), 0, (9, 9)),
+        ]);
+        assert_eq!(world.maps.len(), 1);
+    }
+
+    #[test]
+    fn scheduled_updates_wait_for_their_tick() {
+        let (mut world, mut player) = new_world();
+        world.tick(&mut player);
+
+        world.schedule_update(WorldUpdate::new(WorldUpdateEventType::PickupTreasure((21, 1))), 3);
+        world.update_world(&mut player);
+        world.tick(&mut player);
+        world.tick(&mut player);
+
+        assert_eq!(world.update_queue.len(), 1);
+        assert_eq!(world.active_map().grid[(21, 1)].get_state(), Some(&CommonState::UNCOLLECTED));
+
+        world.tick(&mut player);
+
+        assert!(world.update_queue.is_empty());
+        assert_eq!(world.active_map().grid[(21, 1)].get_state(), Some(&CommonState::COLLECTED));
+        assert_eq!(player.gold_qty(), 500);
+    }
+
+    #[test]
+    fn scheduled_updates_apply_to_the_map_they_were_queued_on() {
+        let (mut world, mut player) = new_world();
+        world.load_map_from_fstr("assets/test_map2.txt").unwrap();
+        assert!(world.spawn_player(&mut player, 1, "west_door"));
+
+        // Loot the chest at (4,8) of the second map after the player has gone back to the first
+        world.schedule_update(WorldUpdate::new(WorldUpdateEventType::PickupTreasure((4, 8))), 2);
+        assert!(world.spawn_player(&mut player, 0, "east_door"));
+        let untouched = world.active_map().grid[(4, 8)].clone();
+        world.tick(&mut player);
+        world.tick(&mut player);
+
+        assert!(world.update_queue.is_empty());
+        assert_eq!(world.map(1).unwrap().grid[(4, 8)].get_state(), Some(&CommonState::COLLECTED));
+        assert_eq!(world.active_map().grid[(4, 8)], untouched);
+        assert_eq!(player.gold_qty(), 250);
+    }
+
+    #[test]
+    fn tile_updates_only_apply_to_their_kind_of_tile() {
+        let (mut world, mut player) = new_world();
+        let wall = world.active_map().grid[(0, 0)].clone();
+        let floor = world.active_map().grid[(22, 1)].clone();
+        let kdl = KeyDoorLink { key_coords: (22, 1), door_coords: (0, 0) };
+        player.keyring.push(kdl);
+
+        // Off the map, and on tiles which are not chests, keys or doors
+        for coords in [(500, 500), (0, 0), (22, 1)] {
+            let pickup = WorldUpdateEventType::PickupTreasure(coords);
+            world.schedule_update(WorldUpdate::new(pickup), 1);
+            world.queue_update(WorldUpdate::new(WorldUpdateEventType::TryOpenDoor(coords)));
+        }
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::KeyPickup(kdl)));
+        world.tick(&mut player);
+
+        assert!(world.update_queue.is_empty());
+        assert_eq!(world.active_map().grid[(0, 0)], wall);
+        assert_eq!(world.active_map().grid[(22, 1)], floor);
+        assert_eq!(player.keyring, [kdl]);
+
+        // A looted chest can't be looted again
+        for _ in 0..2 {
+            world.queue_update(WorldUpdate::new(WorldUpdateEventType::PickupTreasure((21, 1))));
+            world.update_world(&mut player);
+        }
+        assert_eq!(player.gold_qty(), 500);
+    }
+
+    #[test]
+    fn updates_are_processed_by_priority_then_in_queue_order() {
+        let (mut world, mut player) = new_world();
+        let keys = [((28, 1), (24, 21)), ((18, 1), (16, 36)), ((10, 24), (8, 7))]
+            .map(|(key_coords, door_coords)| KeyDoorLink { key_coords, door_coords });
+
+        // Opening the door needs the last key, which is only picked up after the door is tried
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::KeyPickup(keys[0])));
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::Idle));
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::KeyPickup(keys[1])));
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::TryOpenDoor((8, 7))));
+        world.queue_update(WorldUpdate::new(WorldUpdateEventType::KeyPickup(keys[2])));
+        world.update_world(&mut player);
+
+        assert_eq!(player.keyring, keys);
+        assert_eq!(world.active_map().grid[(8, 7)].get_state(), Some(&CommonState::LOCKED));
+
+        // Picked up keys are restyled after the key definition, hiding them even in debug builds
+        let key = world.active_map().grid[(28, 1)].get_properties();
+        assert_eq!((key.draw_character, key.kdl), ('.', None));
+    }
+
+    #[test]
+    fn levers_toggle_their_targets() {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(SWITCH_MAP, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(1, 1, 1));
+
+        world::translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+
+        assert_eq!((player.row(), player.col()), (1, 1));
+        assert_eq!(world.active_map().grid[(1, 2)].get_state(), Some(&CommonState::ACTIVE));
+        assert_eq!(world.active_map().grid[(1, 2)].get_properties().draw_character, '\\');
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::UNLOCKED));
+        assert!(world.active_map().is_passable((1, 4)));
+
+        world::translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+
+        assert_eq!(world.active_map().grid[(1, 2)].get_state(), Some(&CommonState::INACTIVE));
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::LOCKED));
+        assert!(!world.active_map().is_passable((1, 4)));
+    }
+
+    #[test]
+    fn pressure_plates_revert_when_stepped_off() {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(SWITCH_MAP, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(2, 2, 1));
+
+        world::translate(&mut player, &mut world, MoveDirection::DOWN);
+        world.update_world(&mut player);
+
+        assert_eq!((player.row(), player.col()), (3, 2));
+        assert_eq!(world.active_map().grid[(3, 2)].get_state(), Some(&CommonState::ACTIVE));
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::UNLOCKED));
+
+        world::translate(&mut player, &mut world, MoveDirection::UP);
+        world.update_world(&mut player);
+
+        assert_eq!(world.active_map().grid[(3, 2)].get_state(), Some(&CommonState::INACTIVE));
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::LOCKED));
+    }
+
+    #[test]
+    fn plate_release_waits_for_an_empty_doorway() {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(SWITCH_MAP, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(4, 2, 1));
+        let goblin = Npc::new("Goblin", 'g', (3, 2), Behaviour::Idle, Stats::new(5, 1, 0), 1);
+
+        // The goblin holds the plate down while the player walks into the doorway
+        world.active_map_mut().npcs.push(goblin.clone());
+        world.update_world(&mut player);
+        world::translate(&mut player, &mut world, MoveDirection::DOWN);
+        world.update_world(&mut player);
+        assert_eq!((player.row(), player.col()), (3, 4));
+
+        // Released under the player, the door stays open until the doorway is empty
+        world.active_map_mut().npcs.clear();
+        world.update_world(&mut player);
+        assert_eq!(world.active_map().grid[(3, 2)].get_state(), Some(&CommonState::INACTIVE));
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::UNLOCKED));
+
+        world::translate(&mut player, &mut world, MoveDirection::UP);
+        world.tick(&mut player);
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::LOCKED));
+
+        // Pressing the plate again before the doorway empties cancels the pending lock
+        world.active_map_mut().npcs.push(goblin.clone());
+        world.update_world(&mut player);
+        world::translate(&mut player, &mut world, MoveDirection::DOWN);
+        world.update_world(&mut player);
+        world.active_map_mut().npcs.clear();
+        world.update_world(&mut player);
+        world.active_map_mut().npcs.push(goblin);
+        world::translate(&mut player, &mut world, MoveDirection::UP);
+        world.tick(&mut player);
+        world.tick(&mut player);
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::UNLOCKED));
+
+        // The plate still drives the door the right way round
+        world.active_map_mut().npcs.clear();
+        world.update_world(&mut player);
+        assert_eq!(world.active_map().grid[(3, 4)].get_state(), Some(&CommonState::LOCKED));
+    }
+
+    #[test]
+    fn switches_sharing_a_door_each_toggle_it() {
+        let mut world = WorldController::new();
+        let map = b"\
+#######
+#/./|.#
+#######
+~[switches]
+~W(1,1) = (1,4)
+~W(1,3) = (1,4)
+";
+        world.maps.push(WorldMap::from_bytes(map, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(5, 1, 1));
+
+        // Both levers are pulled before either change is applied
+        world.flip_switch((1, 1), true);
+        world.flip_switch((1, 3), true);
+        world.update_world(&mut player);
+
+        assert_eq!(world.active_map().grid[(1, 1)].get_state(), Some(&CommonState::ACTIVE));
+        assert_eq!(world.active_map().grid[(1, 3)].get_state(), Some(&CommonState::ACTIVE));
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::LOCKED));
+
+        world.flip_switch((1, 3), false);
+        world.update_world(&mut player);
+
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::UNLOCKED));
+    }
+
+    /// Subscribes a listener which records every event
+    fn record_events(world: &mut WorldController) -> (ListenerId, Rc<RefCell<Vec<WorldEvent>>>) {
+        let events = Rc::new(RefCell::new(vec![]));
+        let recorded = Rc::clone(&events);
+        let id = world.subscribe(move |event, _, _| recorded.borrow_mut().push(event.clone()));
+        (id, events)
+    }
+
+    #[test]
+    fn listeners_run_after_updates_are_applied() {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(SWITCH_MAP, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(1, 1, 1));
+        let (_, events) = record_events(&mut world);
+
+        // The door is already open by the time its listeners hear of it
+        let opened = Rc::new(RefCell::new(false));
+        let seen = Rc::clone(&opened);
+        world.subscribe(move |event, world, _| {
+            if let WorldEvent::DoorOpened(door) = event {
+                *seen.borrow_mut() = world.active_map().is_passable(*door);
+            }
+        });
+
+        world::translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+
+        assert_eq!(*events.borrow(), vec![
+            WorldEvent::SwitchFlipped { switch: (1, 2), active: true },
+            WorldEvent::DoorOpened((1, 4)),
+        ]);
+        assert!(*opened.borrow());
+    }
+
+    #[test]
+    fn listeners_can_queue_updates_and_unsubscribe() {
+        let mut world = WorldController::new();
+        world.maps.push(WorldMap::from_bytes(SWITCH_MAP, 0, &world.registries).unwrap());
+        let mut player = Player::new('@', NAVector3::new(1, 1, 1));
+
+        // Slam every door shut as soon as it opens
+        let slammer = world.subscribe(|event, world, _| {
+            if let WorldEvent::DoorOpened(door) = event {
+                let close = WorldUpdateEventType::ChangeTileState(*door, CommonState::LOCKED);
+                world.queue_update(WorldUpdate::new(close));
+            }
+        });
+        let (recorder, events) = record_events(&mut world);
+
+        world::translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+
+        assert_eq!(events.borrow().last(), Some(&WorldEvent::DoorClosed((1, 4))));
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::LOCKED));
+
+        assert!(world.unsubscribe(slammer));
+        assert!(world.unsubscribe(recorder));
+        assert!(!world.unsubscribe(recorder));
+
+        world::translate(&mut player, &mut world, MoveDirection::RIGHT);
+        world.update_world(&mut player);
+
+        assert_eq!(events.borrow().len(), 3);
+        assert_eq!(world.active_map().grid[(1, 4)].get_state(), Some(&CommonState::UNLOCKED));
+    }
+}
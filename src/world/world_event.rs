@@ -0,0 +1,57 @@
+//! # World Event
+//!
+//! The outcomes of processed [WorldUpdate](crate::world::WorldUpdate)s, reported to the listeners
+//! registered with [WorldController::subscribe]. A `WorldUpdate` asks for something to happen (a
+//! door to be tried, a chest to be looted), while a `WorldEvent` reports what actually happened,
+//! after the controller has applied it. Achievements, quest logic, message logging and telemetry
+//! react to events without editing the controller.
+//!
+//! #### Version: 0.0.1
+//!
+//! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
+
+// > CRATE
+use crate::shared::treasure::{TreasureID, TreasureQuantity};
+use crate::shared::{KeyDoorLink, WorldCoordinates};
+use crate::Player;
+
+// > SUPER
+use super::{Combatant, WorldController};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An `enum` of everything that can happen in the world
+///
+/// * `KeyPickedUp` - The player picked up a key
+/// * `DoorOpened` - A door was unlocked, by a key or a switch
+/// * `DoorClosed` - A door was locked again by a switch
+/// * `DoorLocked` - The player walked into a locked door without its key
+/// * `SwitchFlipped` - A lever was pulled or pushed back, or a pressure plate was pressed or
+///   released
+/// * `TreasureCollected` - The player looted a chest, receiving its `items`
+/// * `MapEntered` - The player was placed on a map, by a door or a spawn point
+/// * `Damaged` - A combatant hit another for `damage` hit points
+/// * `NpcKilled` - An NPC died, rewarding the player with `xp` experience
+/// * `PlayerDied` - The player's hit points ran out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldEvent {
+    KeyPickedUp(KeyDoorLink),
+    DoorOpened(WorldCoordinates),
+    DoorClosed(WorldCoordinates),
+    DoorLocked(WorldCoordinates),
+    SwitchFlipped { switch: WorldCoordinates, active: bool },
+    TreasureCollected { chest: WorldCoordinates, items: Vec<(TreasureID, TreasureQuantity)> },
+    MapEntered { map_id: usize, entry: WorldCoordinates },
+    Damaged { attacker: Combatant, defender: Combatant, damage: u32 },
+    NpcKilled { coords: WorldCoordinates, name: String, xp: u32 },
+    PlayerDied,
+}
+
+/// A callback run for every [WorldEvent], after the controller has applied the update which
+/// caused it. Listeners may queue further updates on the controller, which are processed before
+/// [WorldController::update_world] returns.
+pub type WorldListener = Box<dyn FnMut(&WorldEvent, &mut WorldController, &Player)>;
+
+/// Identifies a registered [WorldListener], to [unsubscribe](WorldController::unsubscribe) it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(pub(crate) u64);
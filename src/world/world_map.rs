@@ -8,76 +8,176 @@
 //! #### Author: [Zach Meyer / SmlfrySamuri](https://github.com/zachmeyer)
 
 // > USE
+use std::collections::BTreeMap;
+
 use grid::*;
-use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 // > CRATE
 use crate::shared::{
-    traits::{IdentifiableChar, Positionable}, CommonState, KeyDoorLink, Tile
+    traits::Positionable, CommonState, DoorMapLink, KeyDoorLink, 
+    MapEntry, Stats, Tile, WorldCoordinates
 };
+use crate::npc::{Behaviour, Npc};
+use crate::shared::constants::NPC_BASE_STATS;
+use crate::registry::{ItemRegistry, Registries};
 use crate::shared::treasure::*;
+use crate::world::{
+    DIRECTIVE_MARKER, FieldOfView, MapDirective, MapDocument, MapParseError, MapParseErrorKind,
+    MapParseWarning, parse_directives,
+};
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The name of the spawn point the player starts a new game on
+pub const DEFAULT_SPAWN: &str = "start";
+
 /// "Containerizes" the Grid<Tile> of the WorldMap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WorldMap {
     pub id: usize,
     pub grid: Grid<Tile>,
+    /// Every tile the player has seen on this map, remembered between visits
+    pub explored: Grid<bool>,
+    pub npcs: Vec<Npc>,
+    /// The named tiles the player can be placed on, declared with `~S` directives
+    pub spawns: BTreeMap<String, WorldCoordinates>,
 }
 
 impl WorldMap {
-    /// Loads and parses the [WorldMap] from the raw `&[u8]` bytes of a txt file. 
+    /// Loads and parses the [WorldMap] from the raw `&[u8]` bytes of a txt file. The grid has
+    /// exactly one row per map line and one column per character of each line.
     ///
     /// # Arguments
     /// * `bytes` (`&[u8]`) - The bytes of the txt WorldMap file. 
-    pub fn from_bytes(bytes: &[u8], assigned_id: usize) -> Self {
+    /// * `assigned_id` (`usize`) - The id given to the map.
+    /// * `registries` (`&Registries`) - The tile definitions of the map's glyphs and the item
+    ///   definitions `~T` directives may reference.
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] containing ***every*** problem found in the file.
+    pub fn from_bytes(
+        bytes: &[u8],
+        assigned_id: usize,
+        registries: &Registries
+    ) -> Result<Self, MapParseError> {
+        Self::parse(bytes, assigned_id, registries).map(|(map, _)| map)
+    }
+
+    /// Loads and parses the [WorldMap] like [WorldMap::from_bytes], also returning the warnings
+    /// raised for lines of the directive section which were ignored or misplaced (see
+    /// [parse_directives]).
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] containing ***every*** problem found in the file.
+    pub fn parse(
+        bytes: &[u8],
+        assigned_id: usize,
+        registries: &Registries
+    ) -> Result<(Self, Vec<MapParseWarning>), MapParseError> {
         let contents = String::from_utf8_lossy(bytes);
         let lines = extract_map_lines(&contents);
-        let key_door_links = parse_key_door_links(&contents);
-        let treasure_chests = parse_treasure_chests(&contents);
+        let mut errors: Vec<MapParseErrorKind> = vec![];
+        let mut warnings: Vec<MapParseWarning> = vec![];
+        let directives = parse_directives(&contents, &mut errors, &mut warnings);
 
-        let mut grid = Grid::new(
-            lines.len(),
-            lines.first().map_or(0, |line| line.1.len() * 2),
-        );
+        let key_door_links: Vec<(usize, KeyDoorLink)> = directives.iter()
+            .filter_map(|(line, directive)| match directive {
+                MapDirective::KeyDoor(kdl) => Some((*line, *kdl)),
+                _ => None,
+            })
+            .collect();
+        let door_map_links: Vec<(usize, DoorMapLink)> = directives.iter()
+            .filter_map(|(line, directive)| match directive {
+                MapDirective::DoorMap(dml) => Some((*line, dml.clone())),
+                _ => None,
+            })
+            .collect();
+        let spawns: Vec<(usize, String, WorldCoordinates)> = directives.iter()
+            .filter_map(|(line, directive)| match directive {
+                MapDirective::Spawn { coords, name } => Some((*line, name.clone(), *coords)),
+                _ => None,
+            })
+            .collect();
+        let switches: Vec<(usize, WorldCoordinates, Vec<WorldCoordinates>)> = directives.iter()
+            .filter_map(|(line, directive)| match directive {
+                MapDirective::Switch { coords, targets } => {
+                    Some((*line, *coords, targets.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let treasure_chests =
+            build_treasure_chests(&directives, &contents, &registries.items, &mut errors);
+        let npcs = build_npcs(&directives, assigned_id);
+
+        // The grid is measured in tiles: one row per line and one column per character
+        let width = lines.first().map_or(0, |line| line.1.chars().count());
+        let mut grid = Grid::new(lines.len(), width);
 
         let mut key_count = 0;
         let mut locked_door_count = 0;
 
         for (row, line) in lines {
-            for (col, c) in line.char_indices() {
-                let mut tile = Tile::from_char_id(c);
+            let line_width = line.chars().count();
+            if line_width != width {
+                errors.push(MapParseErrorKind::RaggedLine {
+                    line: row + 1, expected: width, found: line_width
+                });
+            }
+
+            for (col, c) in line.chars().enumerate() {
+                let Some(mut tile) = registries.tiles.create(c) else {
+                    errors.push(MapParseErrorKind::UnknownTile {
+                        line: row + 1, column: col + 1, glyph: c
+                    });
+                    continue;
+                };
 
                 tile.get_properties_mut().set_position((row, col));
 
                 if let Tile::Key(_) = tile {
-                    let tr = tile.row();
-                    let tc = tile.col();
-                    tile.get_properties_mut().link_door(
-                        if let Some(kdl) = key_door_links.iter()
-                            .find(|kdl| kdl.key_coords.eq(&(tr, tc))) 
-                        { 
-                            *kdl 
-                        } 
-                        else {
-                            panic!("Invalid key tile location defined for linking door.")
-                        },
-                    );
+                    if let Some((_, kdl)) = key_door_links.iter()
+                        .find(|(_, kdl)| kdl.key_coords == (row, col)) 
+                    {
+                        tile.get_properties_mut().link_door(*kdl);
+                    } else {
+                        errors.push(MapParseErrorKind::UnlinkedKey {
+                            line: row + 1, column: col + 1
+                        });
+                    }
                     key_count += 1;
                 }
-                else if let Tile::Treasure(_, _) = tile {
-                    let tr = tile.row();
-                    let tc = tile.col();
-                    if let Some(treasure) = treasure_chests.iter()
-                        .find(|t| t.world_coords.unwrap() == (tr, tc)) 
+                else if let Tile::Treasure(_, CommonState::UNCOLLECTED) = tile {
+                    if let Some((_, treasure)) = treasure_chests.iter()
+                        .find(|(_, t)| t.world_coords == Some((row, col))) 
                     {
                         tile.get_properties_mut().treasure = Some(treasure.clone());
                     } else {
-                        panic!("Invalid treasure chest tile location defined.")
+                        errors.push(MapParseErrorKind::UnlinkedTreasure {
+                            line: row + 1, column: col + 1
+                        });
+                    }
+                }
+                else if let Tile::Door(_, state) = tile {
+                    if let Some((_, dml)) = door_map_links.iter()
+                        .find(|(_, dml)| dml.door_coords == (row, col))
+                    {
+                        tile.get_properties_mut().link_map(dml.clone());
+                    }
+                    // Doors opened by a switch instead of a key don't need a key
+                    let keyed = key_door_links.iter().any(|(_, kdl)| kdl.door_coords == (row, col));
+                    let switched = switches.iter()
+                        .any(|(_, _, targets)| targets.contains(&(row, col)));
+                    if state == CommonState::LOCKED && (keyed || !switched) {
+                        locked_door_count += 1;
                     }
                 }
-                else if let Tile::Door(_, CommonState::LOCKED) = tile {
-                    locked_door_count += 1;
+                else if tile.is_switch()
+                    && let Some((_, _, targets)) = switches.iter()
+                        .find(|(_, coords, _)| *coords == (row, col))
+                {
+                    tile.get_properties_mut().link_switch(targets.clone());
                 }
 
                 if col < grid.cols() && row < grid.rows() {
@@ -86,18 +186,165 @@ impl WorldMap {
             }
         }
 
+        // Ensure every directive points at a tile of the matching kind
+        for (line, kdl) in &key_door_links {
+            let key_ok = matches!(grid.get(kdl.key_coords.0, kdl.key_coords.1), Some(Tile::Key(_)));
+            let door_ok = matches!(
+                grid.get(kdl.door_coords.0, kdl.door_coords.1),
+                Some(Tile::Door(_, CommonState::LOCKED))
+            );
+            if !key_ok || !door_ok {
+                errors.push(MapParseErrorKind::UnmatchedDirective {
+                    line: *line, directive: directive_at(&contents, *line)
+                });
+            }
+        }
+        for (line, treasure) in &treasure_chests {
+            let coords = treasure.world_coords.unwrap_or_default();
+            if !matches!(grid.get(coords.0, coords.1), Some(Tile::Treasure(_, _))) {
+                errors.push(MapParseErrorKind::UnmatchedDirective {
+                    line: *line, directive: directive_at(&contents, *line)
+                });
+            }
+        }
+
+        for (line, dml) in &door_map_links {
+            if !matches!(grid.get(dml.door_coords.0, dml.door_coords.1), Some(Tile::Door(_, _))) {
+                errors.push(MapParseErrorKind::UnmatchedDirective {
+                    line: *line, directive: directive_at(&contents, *line)
+                });
+            }
+        }
+
+        // Switches toggle doors and levers; plates follow whatever stands on them instead
+        for (line, coords, targets) in &switches {
+            let switch_ok = grid.get(coords.0, coords.1).is_some_and(Tile::is_switch);
+            let targets_ok = targets.iter().all(|target| matches!(
+                grid.get(target.0, target.1), Some(Tile::Door(_, _) | Tile::Lever(_, _))
+            ));
+            if !switch_ok || !targets_ok {
+                errors.push(MapParseErrorKind::UnmatchedDirective {
+                    line: *line, directive: directive_at(&contents, *line)
+                });
+            }
+        }
+
+        // NPCs must start on (and patrol between) passable tiles, one NPC per tile
+        for (idx, (line, npc)) in npcs.iter().enumerate() {
+            let mut waypoints = vec![(npc.row(), npc.col())];
+            if let Behaviour::Patrol { route, .. } = &npc.behaviour {
+                waypoints.extend(route.iter().copied());
+            }
+            let stacked = npcs[..idx].iter()
+                .any(|(_, other)| (other.row(), other.col()) == (npc.row(), npc.col()));
+
+            let passable = waypoints.iter()
+                .all(|c| grid.get(c.0, c.1).is_some_and(Tile::is_passable));
+
+            if stacked || !passable {
+                errors.push(MapParseErrorKind::UnmatchedDirective {
+                    line: *line, directive: directive_at(&contents, *line)
+                });
+            }
+        }
+
+        // Spawn points must be on passable tiles and have unique names
+        for (idx, (line, name, coords)) in spawns.iter().enumerate() {
+            if spawns[..idx].iter().any(|(_, other, _)| other == name) {
+                errors.push(MapParseErrorKind::DuplicateSpawn { line: *line, name: name.clone() });
+            } else if !grid.get(coords.0, coords.1).is_some_and(Tile::is_passable) {
+                errors.push(MapParseErrorKind::ImpassableSpawn {
+                    line: *line, name: name.clone()
+                });
+            }
+        }
+
         // Ensure the map has an appropriate number of keys for unlocking doors
-        match key_count.cmp(&locked_door_count) {
-            std::cmp::Ordering::Greater => panic!(
-                "There are too many keys compared to the number of locked doors."
-            ),
-            std::cmp::Ordering::Less => panic!(
-                "There are too many locked doors compared to the number of keys."
-            ),
-            std::cmp::Ordering::Equal => {},
+        if key_count != locked_door_count {
+            errors.push(MapParseErrorKind::KeyDoorCountMismatch {
+                keys: key_count, locked_doors: locked_door_count
+            });
         }
 
-        Self { id: assigned_id, grid  }
+        if errors.is_empty() {
+            let explored = Grid::new(grid.rows(), grid.cols());
+            let npcs = npcs.into_iter().map(|(_, npc)| npc).collect();
+            let spawns = spawns.into_iter().map(|(_, name, coords)| (name, coords)).collect();
+            Ok((Self { id: assigned_id, grid, explored, npcs, spawns }, warnings))
+        } else {
+            Err(MapParseError { errors, warnings })
+        }
+    }
+
+    /// Loads and parses the [WorldMap] from the raw `&[u8]` bytes of a structured RON
+    /// [MapDocument], returning the warnings raised along the way like [WorldMap::parse].
+    ///
+    /// # Errors
+    /// Returns a [MapParseError] containing ***every*** problem found in the document. Line
+    /// numbers refer to the document rendered as an ASCII map (see [MapDocument::to_ascii]).
+    pub fn parse_document(
+        bytes: &[u8],
+        assigned_id: usize,
+        registries: &Registries
+    ) -> Result<(Self, Vec<MapParseWarning>), MapParseError> {
+        let document = MapDocument::from_ron(&String::from_utf8_lossy(bytes))?;
+        let ascii = document.to_ascii(&registries.tiles)?;
+        Self::parse(ascii.as_bytes(), assigned_id, registries)
+    }
+}
+
+impl WorldMap {
+    /// Remembers every tile in the `fov` as explored
+    pub fn explore(&mut self, fov: &FieldOfView) {
+        for (row, col) in fov.iter_visible() {
+            self.explored[(row, col)] = true;
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the tile at `coords` exists and is passable (see [Tile::is_passable]). NPCs
+    /// standing on the tile are not considered.
+    pub fn is_passable(&self, coords: WorldCoordinates) -> bool {
+        self.grid.get(coords.0, coords.1).is_some_and(Tile::is_passable)
+    }
+
+    /// ## Returns
+    ///
+    /// The NPC standing on the tile at `coords`, if any
+    pub fn npc_at(&self, coords: WorldCoordinates) -> Option<&Npc> {
+        self.npcs.iter().find(|npc| (npc.row(), npc.col()) == coords)
+    }
+
+    /// ## Returns
+    ///
+    /// A ***mutable*** reference to the NPC standing on the tile at `coords`, if any
+    pub fn npc_at_mut(&mut self, coords: WorldCoordinates) -> Option<&mut Npc> {
+        self.npcs.iter_mut().find(|npc| (npc.row(), npc.col()) == coords)
+    }
+
+    /// ## Returns
+    ///
+    /// The tile of the spawn point called `name`, if the map declares it
+    pub fn spawn(&self, name: &str) -> Option<WorldCoordinates> {
+        self.spawns.get(name).copied()
+    }
+
+    /// ## Returns
+    ///
+    /// The tile a [DoorMapLink] leading to this map places the player on, if it exists
+    pub fn entry_point(&self, entry: &MapEntry) -> Option<WorldCoordinates> {
+        match entry {
+            MapEntry::Coords(coords) => Some(*coords),
+            MapEntry::Spawn(name) => self.spawn(name),
+        }
+    }
+
+    /// ## Returns
+    ///
+    /// `true` if the player has seen the tile at `coords` on this map
+    pub fn is_explored(&self, coords: WorldCoordinates) -> bool {
+        self.explored.get(coords.0, coords.1).copied().unwrap_or(false)
     }
 }
 
@@ -105,70 +352,151 @@ impl WorldMap {
 ///
 /// # Arguments
 /// * `contents` (`&str`) - The utf8 (lossy) string created from the bytes of a world map txt file. 
-fn extract_map_lines(contents: &str) -> Vec<(usize, &str)> {
+pub(crate) fn extract_map_lines(contents: &str) -> Vec<(usize, &str)> {
     contents
         .lines()
         .enumerate()
-        .take_while(|(_, l)| !l.starts_with('~'))
+        .take_while(|(_, l)| !l.starts_with(DIRECTIVE_MARKER))
         .collect()
 }
 
-/// Parses [KeyDoorLink](crate::shared::KeyDoorLink) 's from the contents of the converted file
-/// bytes.
+/// Returns the trimmed text of the given ***1-based*** line of the map file
+fn directive_at(contents: &str, line: usize) -> String {
+    contents.lines().nth(line - 1).unwrap_or_default().trim().to_string()
+}
+
+/// Builds the [TreasureCollection](crate::shared::TreasureCollection) of every `~T` directive,
+/// paired with the ***1-based*** line it was defined on.
 ///
 /// # Arguments
+/// * `directives` (`&[(usize, MapDirective)]`) - The parsed directives of the map file.
 /// * `contents` (`&str`) - The utf8 (lossy) string created from the bytes of a world map txt file.
-fn parse_key_door_links(contents: &str) -> Vec<KeyDoorLink> {
-    let re = Regex::new(r"^~K\((\d+),(\d+)\) = D\((\d+),(\d+)\)$").unwrap();
+/// * `items` (`&ItemRegistry`) - The item definitions the directives may reference.
+/// * `errors` (`&mut Vec<MapParseErrorKind>`) - Collects any references to unknown items.
+fn build_treasure_chests(
+    directives: &[(usize, MapDirective)],
+    contents: &str,
+    items: &ItemRegistry,
+    errors: &mut Vec<MapParseErrorKind>
+) -> Vec<(usize, TreasureCollection)> {
+    let mut treasures: Vec<(usize, TreasureCollection)> = vec![];
 
-    contents
-        .lines()
-        .skip_while(|ln| !ln.starts_with('~'))
-        .filter_map(|line| {
-            re.captures(line).map(|cap| KeyDoorLink {
-                key_coords: (cap[1].parse().unwrap(), cap[2].parse().unwrap()),
-                door_coords: (cap[3].parse().unwrap(), cap[4].parse().unwrap()),
-            })
-        })
-        .collect()
+    for (line, directive) in directives {
+        let MapDirective::Treasure { coords, items: contents_of_chest } = directive else {
+            continue;
+        };
+
+        let unknown: Vec<TreasureID> = contents_of_chest.iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !items.contains(*id))
+            .collect();
+        for id in &unknown {
+            errors.push(MapParseErrorKind::UnknownTreasure {
+                line: *line, directive: directive_at(contents, *line), id: *id
+            });
+        }
+
+        if unknown.is_empty() {
+            let tcoll = TreasureCollectionBuilder::new(TreasureCollectionVariant::TreasureChest)
+                .coords(*coords)
+                .items(contents_of_chest)
+                .build();
+
+            treasures.push((*line, tcoll));
+        }
+    }
+
+    treasures
 }
 
-/// Parses [TreasureCollection](crate::shared::TreasureCollection) 's from the contents of the 
-/// converted file bytes.
+/// Builds the [Npc](crate::npc::Npc) of every `~N` directive, paired with the ***1-based*** line
+/// it was defined on. NPCs without `STATS` use the default stats.
 ///
 /// # Arguments
-/// * `contents` (`&str`) - The utf8 (lossy) string created from the bytes of a world map txt file.
-fn parse_treasure_chests(contents: &str) -> Vec<TreasureCollection> {
-    let treasure_re =
-        Regex::new(r"^~T\((\d+),(\d+)\) = \(((?:\d+ = \d+)(?:, \d+ = \d+)*)\)$").unwrap();
-    let mut treasures: Vec<TreasureCollection> = vec![];
-
-    for line in contents.lines() {
-        if let Some(caps) = treasure_re.captures(line) {
-            let y = caps[1].parse().unwrap();
-            let x = caps[2].parse().unwrap();
- 
-            let items_str = caps[3].split(", ");
-
-            let mut tmp_collection: Vec<(TreasureID, TreasureQuantity)> = vec![];
-            for item_def in items_str {
-                let parts: Vec<&str> = item_def.split(" = ").collect();
-                if parts.len() == 2 {
-                    let id: TreasureID = parts[0].parse().unwrap();
-                    let qty: TreasureQuantity = parts[1].parse().unwrap();
- 
-                    tmp_collection.push((id, qty));
-                }
+/// * `directives` (`&[(usize, MapDirective)]`) - The parsed directives of the map file.
+/// * `map_id` (`usize`) - The id of the map, used to seed each NPC's random number generator.
+fn build_npcs(directives: &[(usize, MapDirective)], map_id: usize) -> Vec<(usize, Npc)> {
+    directives.iter()
+        .filter_map(|(line, directive)| match directive {
+            MapDirective::Npc { coords, name, glyph, behaviour, stats } => {
+                let (hp, attack, defense) = stats.unwrap_or(NPC_BASE_STATS);
+
+                // Seed every NPC differently, but the same way each time the map is loaded
+                let seed = ((map_id as u64) << 32) ^ ((coords.0 as u64) << 16) ^ coords.1 as u64;
+                let stats = Stats::new(hp, attack, defense);
+                let npc = Npc::new(name, *glyph, *coords, behaviour.clone(), stats, seed);
+                Some((*line, npc))
             }
+            _ => None,
+        })
+        .collect()
+}
 
-            let tcoll = TreasureCollectionBuilder::new(TreasureCollectionVariant::TreasureChest)
-                .coords((y, x))
-                .items(&tmp_collection)
-                .build();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::traits::ToIdentifiableChar;
+
+    /// Returns the grid lines of a map file ( everything before the first directive )
+    fn source_grid(path: &str) -> Vec<String> {
+        std::fs::read_to_string(path).unwrap()
+            .lines()
+            .take_while(|l| !l.starts_with('~'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Parses a map with the built-in definitions
+    fn parse(bytes: &[u8]) -> Result<WorldMap, MapParseError> {
+        WorldMap::from_bytes(bytes, 0, &Registries::default())
+    }
+
+    #[test]
+    fn grid_dimensions_match_source_file() {
+        for path in ["assets/test_map1.txt", "assets/test_map2.txt"] {
+            let source = source_grid(path);
+            let map = parse(&std::fs::read(path).unwrap()).unwrap();
 
-            treasures.push(tcoll);
+            assert_eq!(map.grid.rows(), source.len(), "{}", path);
+            assert_eq!(map.grid.cols(), source[0].chars().count(), "{}", path);
         }
     }
 
-    treasures
-}
\ No newline at end of file
+    #[test]
+    fn grid_tiles_match_source_characters() {
+        let path = "assets/test_map2.txt";
+        let source = source_grid(path);
+        let map = parse(&std::fs::read(path).unwrap()).unwrap();
+
+        for (row, line) in source.iter().enumerate() {
+            let drawn: String = (0..map.grid.cols())
+                .map(|col| map.grid[(row, col)].to_char_id())
+                .collect();
+            assert_eq!(&drawn, line);
+        }
+    }
+
+    #[test]
+    fn ragged_lines_are_reported() {
+        let result = parse(b"####\n#..\n####\n");
+
+        assert_eq!(
+            result.unwrap_err().errors,
+            vec![MapParseErrorKind::RaggedLine { line: 2, expected: 4, found: 3 }]
+        );
+    }
+
+    #[test]
+    fn rows_may_start_with_water() {
+        let map = parse("#####
+≈≈≈≈≈
+#...#
+#####
+~S(2,1) = start
+".as_bytes()).unwrap();
+
+        assert_eq!(map.grid.rows(), 4);
+        assert_eq!(map.grid[(1, 0)].get_properties().fg, Some(ratatui::style::Color::Blue));
+        assert_eq!(map.spawns.get("start"), Some(&(2, 1)));
+    }
+}
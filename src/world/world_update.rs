@@ -3,26 +3,90 @@
 // > USE
 use std::cmp::Ordering;
 
+// > USE 3P
+use serde::{Deserialize, Serialize};
+
 // > CRATE
-use crate::shared::{CommonState, KeyDoorLink, WorldCoordinates};
+use crate::shared::{CommonState, DoorMapLink, KeyDoorLink, WorldCoordinates};
 use crate::shared::treasure::*;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// An `enum` identifying a participant in combat. NPCs are identified by the tile they stand on
+/// in the active map.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Combatant {
+    Player,
+    Npc(WorldCoordinates),
+}
+
+/// An `enum` of the priorities world updates which are due on the same tick are processed in,
+/// from `Lowest` ( processed last ) to `Highest` ( processed first )
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub enum UpdatePriority {
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+}
+
 /// An `enum` encapsulating different types of events that can occur in the world.
-#[derive(Debug, Eq, PartialEq, Clone)]
-pub(crate) enum WorldUpdateEventType {
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub enum WorldUpdateEventType {
     Idle,
     ChangeTileState(WorldCoordinates, CommonState),
+    /// Switches a tile to the opposite of whatever state it is in when the update is processed
+    ToggleTileState(WorldCoordinates),
     KeyPickup(KeyDoorLink),
     TryOpenDoor(WorldCoordinates),
-    PickupTreasure(WorldCoordinates)
+    PickupTreasure(WorldCoordinates),
+    MapTransition(DoorMapLink),
+    Attack { attacker: Combatant, defender: Combatant },
+    Death(Combatant),
+}
+
+impl WorldUpdateEventType {
+    /// ## Returns
+    ///
+    /// The priority of the event among the updates due on the same tick:
+    ///
+    /// * `Highest` - `ChangeTileState` and `ToggleTileState`, so tiles are in their new state
+    ///   before anything uses them
+    /// * `High` - `KeyPickup`, `TryOpenDoor` and `PickupTreasure`
+    /// * `Normal` - `Attack` and `Death`
+    /// * `Low` - `MapTransition`, so the other updates due on the same tick are applied before the
+    ///   player leaves the map
+    /// * `Lowest` - `Idle`
+    pub fn priority(&self) -> UpdatePriority {
+        match self {
+            WorldUpdateEventType::ChangeTileState(_, _)
+            | WorldUpdateEventType::ToggleTileState(_) => UpdatePriority::Highest,
+            WorldUpdateEventType::KeyPickup(_)
+            | WorldUpdateEventType::TryOpenDoor(_)
+            | WorldUpdateEventType::PickupTreasure(_) => UpdatePriority::High,
+            WorldUpdateEventType::Attack { .. } | WorldUpdateEventType::Death(_) => {
+                UpdatePriority::Normal
+            }
+            WorldUpdateEventType::MapTransition(_) => UpdatePriority::Low,
+            WorldUpdateEventType::Idle => UpdatePriority::Lowest,
+        }
+    }
 }
 
 /// A generic struct to encapsulate world update events
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct WorldUpdate<T: Eq + PartialEq> {
     pub event_type: T,
+    /// The world tick the update is processed on ( or after ). Updates due on tick `0` are
+    /// processed as soon as possible.
+    pub due_tick: u64,
+    /// The order the update was queued in, assigned by
+    /// [WorldController::queue_update](crate::world::WorldController::queue_update)
+    pub sequence: u64,
+    /// The id of the map the update applies to. Updates queued without one apply to the map
+    /// which was active when they were queued, even if another map is active once they're due.
+    pub map_id: Option<usize>,
 }
 
 // IMPL (Generic)
@@ -30,31 +94,45 @@ impl<T> WorldUpdate<T>
 where
     T: Eq + PartialEq + Clone
 {
-    /// Creates a new `WorldUpdate` with the specified event type.
+    /// Creates a new `WorldUpdate` with the specified event type, due immediately.
     pub fn new(event_type: T) -> Self {
-        Self {
-            event_type
-        }
+        Self::at_tick(event_type, 0)
     }
     
     /// Creates a new `WorldUpdate` with the specified event type and no payload.
     pub fn with_no_payload(event_type: T) -> Self {
+        Self::new(event_type)
+    }
+
+    /// Creates a new `WorldUpdate` with the specified event type, due on the given world tick
+    /// (see [WorldController::schedule_update](crate::world::WorldController::schedule_update)
+    /// to schedule relative to the current tick)
+    pub fn at_tick(event_type: T, due_tick: u64) -> Self {
         Self {
-            event_type
+            event_type,
+            due_tick,
+            sequence: 0,
+            map_id: None,
         }
     }
+
+    /// Sets the map the update applies to, in place of the map active when it is queued
+    pub fn on_map(mut self, map_id: usize) -> Self {
+        self.map_id = Some(map_id);
+        self
+    }
 }
 
 // Specific implementation for WorldUpdateEventType
 impl Ord for WorldUpdate<WorldUpdateEventType> 
 {
-    /// Compares two `WorldUpdate` instances based on their event types.
+    /// Orders `WorldUpdate` instances by the order they are processed in, which is the order a
+    /// `BinaryHeap` pops them in: the earliest due tick first, then the highest
+    /// [priority](WorldUpdateEventType::priority), then the first queued.
     fn cmp(&self, other: &Self) -> Ordering {
-        match (&self.event_type, &other.event_type) {
-            (WorldUpdateEventType::ChangeTileState(_, _), _) => Ordering::Greater,
-            (_, WorldUpdateEventType::Idle) => Ordering::Less,
-            (_, _) => Ordering::Equal,
-        }
+        other.due_tick.cmp(&self.due_tick)
+            .then_with(|| self.event_type.priority().cmp(&other.event_type.priority()))
+            .then_with(|| other.sequence.cmp(&self.sequence))
     }
 }
 
@@ -64,4 +142,52 @@ impl PartialOrd for WorldUpdate<WorldUpdateEventType>
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
-}
\ No newline at end of file
+}
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BinaryHeap;
+
+    /// Creates an update as if it had been queued `sequence`-th, due on `due_tick`
+    fn update(
+        event_type: WorldUpdateEventType,
+        due_tick: u64,
+        sequence: u64
+    ) -> WorldUpdate<WorldUpdateEventType> {
+        WorldUpdate { event_type, due_tick, sequence, map_id: None }
+    }
+
+    /// Pops every update of the heap, in processing order
+    fn drain(mut heap: BinaryHeap<WorldUpdate<WorldUpdateEventType>>) -> Vec<u64> {
+        std::iter::from_fn(|| heap.pop()).map(|update| update.sequence).collect()
+    }
+
+    #[test]
+    fn pops_by_due_tick_then_priority_then_sequence() {
+        let attack = WorldUpdateEventType::Attack {
+            attacker: Combatant::Player, defender: Combatant::Npc((1, 2))
+        };
+        let heap = BinaryHeap::from(vec![
+            update(WorldUpdateEventType::Idle, 0, 0),
+            update(WorldUpdateEventType::PickupTreasure((1, 1)), 0, 1),
+            update(attack, 0, 2),
+            update(WorldUpdateEventType::ChangeTileState((1, 1), CommonState::UNLOCKED), 0, 3),
+            update(WorldUpdateEventType::TryOpenDoor((2, 2)), 0, 4),
+            update(WorldUpdateEventType::ChangeTileState((1, 1), CommonState::LOCKED), 5, 5),
+            update(WorldUpdateEventType::Idle, 2, 6),
+        ]);
+
+        assert_eq!(drain(heap), vec![3, 1, 4, 2, 0, 6, 5]);
+    }
+
+    #[test]
+    fn equal_priorities_pop_in_queue_order() {
+        let heap: BinaryHeap<_> = [7, 2, 9, 0, 4, 1, 8, 3, 6, 5].into_iter()
+            .map(|sequence| update(WorldUpdateEventType::TryOpenDoor((1, 1)), 0, sequence))
+            .collect();
+
+        assert_eq!(drain(heap), (0..10).collect::<Vec<_>>());
+    }
+}
@@ -1,15 +1,29 @@
+// > USE 3P
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+
 // > CRATE
-use crate::shared::extlib::{RatatuiRect, RatatuiWidget, RatatuiBuffer};
+use crate::shared::constants::{TILE_HEIGHT, TILE_WIDTH};
+use crate::shared::extlib::{RatatuiRect, RatatuiStatefulWidget, RatatuiBuffer};
+use crate::shared::traits::{Positionable, ToIdentifiableChar};
+use crate::shared::{Column, Row, Tile};
 use crate::Player;
 
 // > SUPER
-use super::WorldController;
+use super::{Camera, Visibility, WorldController};
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Encapsulates the View which is the renderable component of the `WorldController`
+///
+/// The view is the only place where logical tile coordinates are converted into screen cells:
+/// each tile occupies [TILE_WIDTH] x [TILE_HEIGHT] cells, with its glyph drawn in the top-left
+/// cell and the remaining cells left blank.
+///
+/// Only tiles in the player's field of view are drawn as they are; tiles the player remembers
+/// are drawn dimmed and tiles the player has never seen are left blank.
 pub struct WorldView<'wview> {
-    world: &'wview WorldController<'wview>,
+    world: &'wview WorldController,
     player: &'wview Player,
 }
 
@@ -34,18 +48,111 @@ impl<'wview> WorldView<'wview> {
     }
 }
 
-impl<'wview> RatatuiWidget for WorldView<'wview> {
+impl<'wview> RatatuiStatefulWidget for WorldView<'wview> {
+    type State = Camera;
 
-    /// Renders the world view within the specified area and buffer.
+    /// Renders the visible window of the active map within the specified area and buffer,
+    /// following the player with the `camera`.
     ///
     /// # Arguments
     ///
     /// * `area` - The area to render the world view.
     /// * `buf` - The buffer to render the world view into.
-    fn render(self, area: RatatuiRect, buf: &mut RatatuiBuffer) {
-            let world = self.world;
-            let map_widget = world.generate_map(self.player);
+    /// * `camera` - The camera which determines the visible window of the map.
+    fn render(self, area: RatatuiRect, buf: &mut RatatuiBuffer, camera: &mut Camera) {
+        let map = self.world.active_map();
+        let map_width = map.grid.cols();
+        let map_height = map.grid.rows();
+
+        let (screen_width, screen_height) = tiles_to_cells(map_height, map_width);
+        let map_area = centered_rect(
+            screen_width.saturating_add(2), 
+            screen_height.saturating_add(2), 
+            area
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White));
+
+        let inner = block.inner(map_area);
+
+        // Only the window of tiles that fits inside the block is rendered
+        let view = (
+            (inner.height / TILE_HEIGHT) as usize,
+            (inner.width / TILE_WIDTH) as usize
+        );
+        camera.follow((self.player.row(), self.player.col()), view, (map_height, map_width));
+        let (row_offset, col_offset) = camera.offset();
+
+        let style = Style::default().bg(Color::Black);
+        let player_style = style.fg(Color::White);
+        let npc_style = style.fg(Color::LightRed);
+        let remembered_style = style.fg(Color::DarkGray).add_modifier(Modifier::DIM);
+        let fov = self.world.field_of_view(self.player);
 
-            map_widget.render(area, buf);
+        for row in row_offset..map_height.min(row_offset + view.0) {
+            for col in col_offset..map_width.min(col_offset + view.1) {
+                let (glyph, tile_style) = if row == self.player.row() && col == self.player.col() {
+                    (self.player.to_char_id(), player_style)
+                } else {
+                    let tile = &map.grid[(row, col)];
+                    match fov.visibility(map, (row, col)) {
+                        // NPCs are only seen while in view
+                        Visibility::Visible => match map.npc_at((row, col)) {
+                            Some(npc) => (npc.to_char_id(), npc_style),
+                            None => (tile.to_char_id(), tile_style(style, tile)),
+                        },
+                        Visibility::Remembered => (tile.to_char_id(), remembered_style),
+                        Visibility::Unseen => (' ', style),
+                    }
+                };
+
+                let (x, y) = tiles_to_cells(row - row_offset, col - col_offset);
+                draw_tile(buf, inner.x + x, inner.y + y, glyph, tile_style);
+            }
         }
-}
\ No newline at end of file
+
+        block.render(map_area, buf);
+    }
+}
+
+/// Converts a ( row, col ) extent or offset in tiles into an ( x, y ) extent or offset in screen
+/// cells
+fn tiles_to_cells(rows: Row, cols: Column) -> (u16, u16) {
+    (
+        (cols as u16).saturating_mul(TILE_WIDTH),
+        (rows as u16).saturating_mul(TILE_HEIGHT)
+    )
+}
+
+/// Applies a tile's defined colors on top of the base `style`
+fn tile_style(style: Style, tile: &Tile) -> Style {
+    let props = tile.get_properties();
+    let style = props.fg.map_or(style, |fg| style.fg(fg));
+    props.bg.map_or(style, |bg| style.bg(bg))
+}
+
+/// Draws a single tile whose top-left screen cell is ( `x`, `y` )
+fn draw_tile(buf: &mut RatatuiBuffer, x: u16, y: u16, glyph: char, style: Style) {
+    for dy in 0..TILE_HEIGHT {
+        for dx in 0..TILE_WIDTH {
+            if let Some(cell) = buf.cell_mut((x + dx, y + dy)) {
+                cell.set_char(if dx == 0 && dy == 0 { glyph } else { ' ' }).set_style(style);
+            }
+        }
+    }
+}
+
+/// Creates a rect of the given size centered within `r` (clipped to `r`)
+fn centered_rect(width: u16, height: u16, r: RatatuiRect) -> RatatuiRect {
+    let x = r.x + (r.width.saturating_sub(width)) / 2;
+    let y = r.y + (r.height.saturating_sub(height)) / 2;
+
+    RatatuiRect::new(
+        x,
+        y,
+        width.min(r.width),
+        height.min(r.height)
+    )
+}
@@ -0,0 +1,209 @@
+// > USE 3P
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+// > USE CRATE
+use ferrisyum::game::{Game, GameAction, GameStatus};
+use ferrisyum::save::SaveError;
+use ferrisyum::shared::extlib::NAVector3;
+use ferrisyum::shared::traits::Positionable;
+use ferrisyum::shared::{CommonState, MoveDirection};
+use ferrisyum::world::TimeMode;
+use ferrisyum::Player;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Creates a game on `test_map1.txt` with the player at its usual starting position
+fn new_game() -> Game {
+    let mut game = Game::new(Player::new('@', NAVector3::new(2, 1, 1)));
+    game.load_map("assets/test_map1.txt").unwrap();
+    game.load_map("assets/test_map2.txt").unwrap();
+    game
+}
+
+/// Expands `(direction, steps)` pairs into a sequence of move actions
+fn walk(path: &[(MoveDirection, usize)]) -> Vec<GameAction> {
+    path.iter()
+        .flat_map(|&(direction, steps)| std::iter::repeat_n(GameAction::Move(direction), steps))
+        .collect()
+}
+
+/// Renders the game into a headless terminal and returns its lines
+fn snapshot(game: &mut Game) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    terminal.draw(|f| game.draw(f)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect()
+}
+
+const UP: MoveDirection = MoveDirection::UP;
+const DOWN: MoveDirection = MoveDirection::DOWN;
+const LEFT: MoveDirection = MoveDirection::LEFT;
+const RIGHT: MoveDirection = MoveDirection::RIGHT;
+
+#[test]
+fn collect_key_open_door_and_loot_chest() {
+    let mut game = new_game();
+
+    // Walk to the key at (28,1), which opens the door at (24,21)
+    game.run(walk(&[
+        (DOWN, 5), (RIGHT, 10), (DOWN, 1), (RIGHT, 1), (DOWN, 1), (RIGHT, 2), (DOWN, 6),
+        (RIGHT, 8), (DOWN, 4), (LEFT, 8), (DOWN, 4), (LEFT, 6), (DOWN, 4), (LEFT, 7),
+        (DOWN, 2), (LEFT, 1),
+    ])).unwrap();
+
+    assert_eq!((game.player().row(), game.player().col()), (28, 1));
+    assert_eq!(game.player().keyring.len(), 1);
+    assert_eq!(game.player().keyring[0].door_coords, (24, 21));
+
+    // Walk through the door and stand next to the chest at (23,20)
+    game.run(walk(&[(UP, 3), (RIGHT, 20), (UP, 2)])).unwrap();
+
+    assert_eq!((game.player().row(), game.player().col()), (23, 21));
+    assert!(game.player().keyring.is_empty());
+    assert_eq!(
+        game.world().active_map().grid[(24, 21)].get_state(),
+        Some(&CommonState::UNLOCKED)
+    );
+
+    game.apply(GameAction::PickupTreasure).unwrap();
+
+    assert_eq!(game.player().gold_qty(), 450);
+    assert_eq!(
+        game.world().active_map().grid[(23, 20)].get_state(),
+        Some(&CommonState::COLLECTED)
+    );
+    assert!(snapshot(&mut game).iter().any(|line| line.contains("Gold: 450")));
+    assert!(game.messages().iter().any(|message| message.text.starts_with("You found 450 gold")));
+}
+
+#[test]
+fn locked_door_blocks_without_key() {
+    let mut game = new_game();
+
+    // The door at (8,7) is directly below (7,7)
+    game.run(walk(&[(DOWN, 5), (RIGHT, 5), (DOWN, 1)])).unwrap();
+    assert_eq!((game.player().row(), game.player().col()), (7, 7));
+
+    game.apply(GameAction::Move(DOWN)).unwrap();
+
+    assert_eq!((game.player().row(), game.player().col()), (7, 7));
+    assert_eq!(
+        game.world().active_map().grid[(8, 7)].get_state(),
+        Some(&CommonState::LOCKED)
+    );
+}
+
+#[test]
+fn messages_panel_shows_and_scrolls_the_log() {
+    let mut game = new_game();
+    game.run(walk(&[(DOWN, 5), (RIGHT, 5), (DOWN, 1)])).unwrap();
+
+    // Walk into the locked door at (8,7) twice, then try to drink a potion
+    game.run([GameAction::Move(DOWN), GameAction::Move(DOWN), GameAction::UsePotion]).unwrap();
+
+    let lines = snapshot(&mut game);
+    assert!(lines.iter().any(|line| line.contains("Messages")));
+    assert!(lines.iter().any(|line| line.contains("The door is locked. (x2)")));
+    assert!(lines.iter().any(|line| line.contains("You have no potions.")));
+
+    // Scrolling is clamped to the log, which fits in the panel
+    game.apply(GameAction::ScrollMessagesUp).unwrap();
+    snapshot(&mut game);
+    assert_eq!(game.ui.message_scroll, 0);
+
+    // The history survives hiding the panel
+    game.apply(GameAction::ToggleMessages).unwrap();
+    assert!(!snapshot(&mut game).iter().any(|line| line.contains("Messages")));
+    assert_eq!(game.messages().iter().last().unwrap().text, "You have no potions.");
+}
+
+#[test]
+fn toggling_panels_changes_layout() {
+    let mut game = new_game();
+
+    let lines = snapshot(&mut game);
+    assert!(lines.iter().any(|line| line.contains("Inventory")));
+    assert!(lines.iter().any(|line| line.contains("Stats")));
+    assert!(lines.iter().any(|line| line.contains('@')));
+
+    game.run([GameAction::ToggleInventory, GameAction::ToggleStats]).unwrap();
+
+    let lines = snapshot(&mut game);
+    assert!(!lines.iter().any(|line| line.contains("Inventory")));
+    assert!(!lines.iter().any(|line| line.contains("Stats")));
+}
+
+#[test]
+fn quickload_without_a_save_keeps_playing() {
+    // A fresh, empty directory of this test's own, so no quicksave can be found
+    let dir = std::env::temp_dir().join(format!(
+        "ferrisyum-{}-quickload_without_a_save_keeps_playing", std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut game = new_game();
+    game.set_quicksave_path(dir.join("quicksave.ron"));
+    game.run(walk(&[(DOWN, 2)])).unwrap();
+    assert_eq!((game.player().row(), game.player().col()), (3, 2));
+
+    let result = game.apply(GameAction::QuickLoad);
+
+    assert!(matches!(result, Err(SaveError::Io(_))));
+    assert_eq!((game.player().row(), game.player().col()), (3, 2));
+    assert!(game.messages().iter().any(|message| message.text.starts_with("Load failed")));
+
+    // The game carries on from where it was
+    assert_eq!(game.apply(GameAction::Move(DOWN)).unwrap(), GameStatus::Running);
+    assert_eq!((game.player().row(), game.player().col()), (4, 2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_reports_a_lost_game() {
+    let mut player = Player::new('@', NAVector3::new(2, 1, 1));
+    player.stats.hp = 1;
+    let mut game = Game::new(player);
+    game.load_map("assets/test_map1.txt").unwrap();
+    game.load_map("assets/test_map2.txt").unwrap();
+    assert!(game.spawn_player(1, "west_door"));
+
+    // Walk towards the goblin chasing from (4,12), which hits harder than one hit point
+    let status = game.run(walk(&[(RIGHT, 12)])).unwrap();
+
+    assert!(game.player().stats.is_dead());
+    assert_eq!(status, GameStatus::GameOver);
+}
+
+#[test]
+fn quit_stops_the_action_sequence() {
+    let mut game = new_game();
+
+    let status = game.run([GameAction::Quit, GameAction::Move(DOWN)]).unwrap();
+
+    assert_eq!(status, GameStatus::Quit);
+    assert_eq!((game.player().row(), game.player().col()), (1, 2));
+}
+
+#[test]
+fn npcs_move_on_ticks_only_in_real_time() {
+    let mut game = new_game();
+    assert!(game.spawn_player(1, "west_door"));
+
+    // The guard patrols away from (8,1); ticks alone never move it in turn-based mode
+    let guard = |game: &Game| {
+        let npc = &game.world().active_map().npcs[2];
+        (npc.row(), npc.col())
+    };
+    game.advance(100);
+    assert_eq!(guard(&game), (8, 1));
+
+    game.set_time_mode(TimeMode::RealTime);
+    game.advance(100);
+    assert_ne!(guard(&game), (8, 1));
+    assert_eq!(game.world().ticks, 200);
+}